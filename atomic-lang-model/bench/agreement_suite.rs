@@ -3,7 +3,7 @@
 //! Tests subject-verb agreement across center-embedded structures to evaluate
 //! the atomic language model's handling of long-distance dependencies.
 
-use atomic_lang_model::*;
+use atomic_lang_model_python::*;
 use std::collections::HashMap;
 
 /// Test case for subject-verb agreement
@@ -3,7 +3,7 @@
 //! Tests syntactic processing independent of semantic content using
 //! semantically anomalous but syntactically well-formed sentences.
 
-use atomic_lang_model::*;
+use atomic_lang_model_python::*;
 use std::collections::HashMap;
 
 /// Test case for colorless green evaluation
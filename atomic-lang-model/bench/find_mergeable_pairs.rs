@@ -0,0 +1,37 @@
+//! Pair-finding on a 50-item workspace
+//!
+//! The items cycle through heads of unrelated categories, so each one can
+//! merge with only a few of the others.
+
+use atomic_lang_model_python::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn lexicon() -> Vec<LexItem> {
+    vec![
+        LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+        LexItem::new("student", &[Feature::Cat(Category::N)]),
+        LexItem::new("left", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+        LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D), Feature::Sel(Category::D)]),
+        LexItem::new("that", &[Feature::Cat(Category::C), Feature::Sel(Category::V)]),
+        LexItem::new("quickly", &[Feature::Cat(Category::Adv), Feature::Adj(Category::V)]),
+        LexItem::new("in", &[Feature::Cat(Category::P), Feature::Sel(Category::D), Feature::Adj(Category::V)]),
+        LexItem::new("CTX_DRIVE", &[Feature::Cat(Category::Context), Feature::Sel(Category::Command)]),
+        LexItem::new("MOTOR_CMD_START", &[Feature::Cat(Category::Command), Feature::Sel(Category::State)]),
+        LexItem::new("VOLTAGE_SPIKE", &[Feature::Cat(Category::State)]),
+    ]
+}
+
+fn bench_find_mergeable_pairs(c: &mut Criterion) {
+    let lexicon = lexicon();
+    let mut workspace = Workspace::new(4096);
+    for item in lexicon.iter().cycle().take(50) {
+        workspace.add_lex(item);
+    }
+
+    c.bench_function("find_mergeable_pairs/50_items", |b| {
+        b.iter(|| find_mergeable_pairs(&workspace))
+    });
+}
+
+criterion_group!(benches, bench_find_mergeable_pairs);
+criterion_main!(benches);
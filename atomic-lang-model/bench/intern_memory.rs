@@ -0,0 +1,68 @@
+//! Peak heap usage while parsing a batch of mission logs
+//!
+//! Parses 1000 logs in one reused `Workspace` and keeps every tree, as a
+//! batch report would. Compare the two configurations:
+//!
+//! ```text
+//! cargo bench --bench intern_memory
+//! cargo bench --bench intern_memory --features intern
+//! ```
+
+use atomic_lang_model_python::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct PeakAlloc;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+const LOGS: [&[&str]; 3] = [
+    &["CTX_DRIVE", "MOTOR_CMD_START", "CURRENT_DRAW", "VOLTAGE_SPIKE"],
+    &["CTX_SCIENCE", "INSTRUMENT_PWR_ON", "TEMP_INSTRUMENT", "VOLTAGE_SPIKE"],
+    &["CTX_DRIVE", "MOTOR_CMD_STOP", "WHEEL_RPM", "VOLTAGE_SPIKE"],
+];
+
+fn main() {
+    let lexicon = space_operations_lexicon();
+    let mut workspace = Workspace::new(4096);
+    let baseline = LIVE.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+
+    let mut trees = Vec::with_capacity(1000);
+    for log in LOGS.iter().cycle().take(1000) {
+        workspace.reset();
+        for event in log.iter() {
+            workspace.add_lex(lexicon.iter().find(|item| item.phon == *event).unwrap());
+        }
+        trees.push(derive(&mut workspace, 100).unwrap());
+    }
+
+    let mode = if cfg!(feature = "intern") { "interned" } else { "per-leaf" };
+    println!(
+        "{} phonology: {} trees, peak {} bytes, retained {} bytes",
+        mode,
+        trees.len(),
+        PEAK.load(Ordering::Relaxed) - baseline,
+        LIVE.load(Ordering::Relaxed) - baseline,
+    );
+}
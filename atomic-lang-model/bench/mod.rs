@@ -9,7 +9,7 @@
 pub mod agreement_suite;
 pub mod colorless_green;
 
-use atomic_lang_model::*;
+use atomic_lang_model_python::*;
 use agreement_suite::*;
 use colorless_green::*;
 use std::time::Instant;
@@ -0,0 +1,36 @@
+//! Serial vs parallel `parse_all`
+//!
+//! Runs the same exhaustive parse on a one-thread rayon pool and on the
+//! default pool. Every "d" may select any other constituent, so each step
+//! offers many mergeable pairs to branch on.
+
+use atomic_lang_model_python::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn lexicon() -> Vec<LexItem> {
+    vec![
+        LexItem::new("d", &[Feature::Cat(Category::D), Feature::Optional(Box::new(Feature::Sel(Category::D)))]),
+        LexItem::new("e", &[Feature::Cat(Category::D)]),
+    ]
+}
+
+fn bench_parse_all(c: &mut Criterion) {
+    let lexicon = lexicon();
+    let sentence = "d d d e d d";
+    let serial_pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+
+    let serial = serial_pool.install(|| parse_all(sentence, &lexicon, 100));
+    assert_eq!(serial, parse_all(sentence, &lexicon, 100));
+
+    let mut group = c.benchmark_group("parse_all");
+    group.bench_function("serial", |b| {
+        b.iter(|| serial_pool.install(|| parse_all(sentence, &lexicon, 100)))
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| parse_all(sentence, &lexicon, 100))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_all);
+criterion_main!(benches);
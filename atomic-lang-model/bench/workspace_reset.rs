@@ -0,0 +1,43 @@
+//! Reusing one `Workspace` vs allocating a fresh one per log
+//!
+//! Mirrors a streaming loop over many short mission logs.
+
+use atomic_lang_model_python::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const LOG: [&str; 3] = ["CTX_DRIVE", "MOTOR_CMD_START", "VOLTAGE_SPIKE"];
+
+fn load(workspace: &mut Workspace, lexicon: &[LexItem]) {
+    for event in LOG {
+        workspace.add_lex(lexicon.iter().find(|item| item.phon == event).unwrap());
+    }
+}
+
+fn bench_workspace_reset(c: &mut Criterion) {
+    let lexicon = space_operations_lexicon();
+
+    let mut group = c.benchmark_group("workspace");
+    group.bench_function("fresh", |b| {
+        b.iter(|| {
+            for _ in 0..1000 {
+                let mut workspace = Workspace::new(4096);
+                load(&mut workspace, &lexicon);
+                derive(&mut workspace, 100).unwrap();
+            }
+        })
+    });
+    group.bench_function("reset", |b| {
+        let mut workspace = Workspace::new(4096);
+        b.iter(|| {
+            for _ in 0..1000 {
+                workspace.reset();
+                load(&mut workspace, &lexicon);
+                derive(&mut workspace, 100).unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_workspace_reset);
+criterion_main!(benches);
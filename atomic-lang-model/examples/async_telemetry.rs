@@ -0,0 +1,48 @@
+//! Feed a telemetry stream through `AsyncIncrementalParser`
+//!
+//! Events arrive as a `Stream<Item = String>`; a separate task reports
+//! anomalies as the parser flags them.
+//!
+//! Run with `cargo run --example async_telemetry --features tokio`.
+
+use atomic_lang_model_python::*;
+use tokio_stream::{Stream, StreamExt};
+
+async fn monitor(lexicon: &[LexItem], mut events: impl Stream<Item = String> + Unpin) {
+    let (mut parser, mut anomalies) = AsyncIncrementalParser::new(lexicon);
+    let reporter = tokio::spawn(async move {
+        while let Some(anomaly) = anomalies.recv().await {
+            println!("  {}", anomaly);
+        }
+    });
+
+    while let Some(event) = events.next().await {
+        if let Err(e) = parser.feed(&event).await {
+            println!("  {}: {}", event, e);
+        }
+    }
+    if let Err(e) = parser.finish().await {
+        println!("  {}", e);
+    }
+    let state = parser.current_state();
+    println!("  complete: {}, open constituents: {}", state.is_successful(), state.items.len());
+
+    // Closing the channel lets the reporter drain and finish
+    drop(parser);
+    reporter.await.unwrap();
+}
+
+#[tokio::main]
+async fn main() {
+    let lexicon = space_operations_lexicon();
+    let logs = [
+        vec!["CTX_DRIVE", "MOTOR_CMD_START", "CURRENT_DRAW", "VOLTAGE_SPIKE"],
+        vec!["CTX_STANDBY", "VOLTAGE_SPIKE", "GLITCH"],
+    ];
+
+    for log in logs {
+        println!("{}", log.join(" "));
+        let events = tokio_stream::iter(log.into_iter().map(String::from));
+        monitor(&lexicon, events).await;
+    }
+}
@@ -0,0 +1,52 @@
+#!/usr/bin/env python3
+"""
+Batch vs per-log mission log validation
+=======================================
+
+Times validating thousands of short logs with one `validate_mission_log`
+call per log against a single `validate_mission_logs` call for the batch.
+Build the extension module first (e.g. `maturin develop --release`).
+"""
+
+import random
+import time
+
+from atomic_lang_model_python import validate_mission_log, validate_mission_logs
+
+EVENTS = [
+    "CTX_DRIVE", "CTX_SCIENCE", "CTX_STANDBY",
+    "MOTOR_CMD_START", "CURRENT_DRAW", "VOLTAGE_SPIKE", "WHEEL_RPM",
+]
+
+
+def make_logs(count, length, seed=0):
+    rng = random.Random(seed)
+    return [[rng.choice(EVENTS) for _ in range(length)] for _ in range(count)]
+
+
+def best_of(runs, fn):
+    times = []
+    for _ in range(runs):
+        start = time.perf_counter()
+        fn()
+        times.append(time.perf_counter() - start)
+    return min(times)
+
+
+def main():
+    logs = make_logs(count=10_000, length=4)
+
+    per_log = [validate_mission_log(log) for log in logs]
+    assert validate_mission_logs(logs) == per_log
+
+    t_single = best_of(5, lambda: [validate_mission_log(log) for log in logs])
+    t_batch = best_of(5, lambda: validate_mission_logs(logs))
+
+    print(f"{len(logs)} logs")
+    print(f"  one call per log: {t_single * 1000:8.2f} ms")
+    print(f"  one batched call: {t_batch * 1000:8.2f} ms")
+    print(f"  speedup:          {t_single / t_batch:8.2f}x")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,9 @@
+//! `size_check` without the parser
+//!
+//! Handles its arguments the same way, so the difference between the two
+//! binaries' `.text` sections is the code `parse_sentence` pulls in.
+
+fn main() {
+    let sentence = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    std::process::exit(i32::from(sentence.is_empty()));
+}
@@ -0,0 +1,17 @@
+//! Smallest program that parses a sentence
+//!
+//! `tests/binary_size.rs` builds this in release mode and checks the size of
+//! its `.text` section, so it should only ever call `parse_sentence`. The
+//! exit status reports whether the arguments parsed.
+
+use atomic_lang_model_python::{parse_sentence, Category, Feature, LexItem};
+
+fn main() {
+    let lexicon = [
+        LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+        LexItem::new("student", &[Feature::Cat(Category::N)]),
+        LexItem::new("left", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+    ];
+    let sentence = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    std::process::exit(i32::from(parse_sentence(&sentence, &lexicon).is_err()));
+}
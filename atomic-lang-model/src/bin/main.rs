@@ -1,89 +0,0 @@
-//! Atomic Language Model - CLI Demo
-//! 
-//! Command-line interface demonstrating recursive language generation and parsing
-//! with provable mathematical properties.
-
-use atomic_lang_model::*;
-
-fn main() {
-    println!("🧬 Atomic Language Model - Recursive Grammar Demo");
-    println!("{}", "=".repeat(60));
-    
-    // Demonstrate aⁿbⁿ generation (proof of recursion)
-    println!("\n📐 Mathematical Proof: aⁿbⁿ Generation");
-    println!("{}", "-".repeat(40));
-    
-    for n in 0..=5 {
-        match generate_pattern("an_bn", n) {
-            Ok(pattern) => {
-                let display = if pattern.is_empty() { "ε (empty)" } else { &pattern };
-                println!("n={}: {}", n, display);
-            }
-            Err(e) => println!("n={}: Error - {}", n, e),
-        }
-    }
-    
-    // Test recursive parsing capability
-    println!("\n🔍 Parsing Test: Recursive Structures");
-    println!("{}", "-".repeat(40));
-    
-    let lexicon = test_lexicon();
-    let test_sentences = vec![
-        "the student left",
-        "the tutor smiled",
-        "the teacher arrived",
-    ];
-    
-    for sentence in test_sentences {
-        match parse_sentence(sentence, &lexicon) {
-            Ok(tree) => {
-                println!("✅ '{}' → {}", sentence, tree.linearize());
-                println!("   Category: {:?}, Complete: {}", tree.label, tree.is_complete());
-            }
-            Err(e) => {
-                println!("❌ '{}' → Error: {}", sentence, e);
-            }
-        }
-    }
-    
-    // Memory and performance metrics
-    println!("\n📊 Performance Metrics");
-    println!("{}", "-".repeat(40));
-    
-    let mut workspace = Workspace::new(1024);
-    workspace.add_lex(&lexicon[0]); // "the"
-    workspace.add_lex(&lexicon[2]); // "student"
-    workspace.add_lex(&lexicon[8]); // "left"
-    
-    println!("Memory usage: {} bytes", workspace.memory_usage());
-    println!("Objects in workspace: {}", workspace.items.len());
-    println!("Binary optimized for: <50kB total size");
-    
-    // Demonstrate unbounded recursion principle
-    println!("\n♾️  Unbounded Recursion Demonstration");
-    println!("{}", "-".repeat(40));
-    
-    println!("Generating increasingly complex patterns...");
-    for n in 6..=10 {
-        if can_generate("an_bn", n) {
-            println!("✅ Can generate a^{}b^{} (length: {})", n, n, 2*n);
-        } else {
-            println!("❌ Cannot generate a^{}b^{}", n, n);
-        }
-    }
-    
-    println!("\n🎯 Theoretical Capacity: INFINITE");
-    println!("🔬 Practical Limit: Memory bounded");
-    println!("📈 Complexity: Polynomial time, exponential DFA states");
-    
-    // Show formal properties
-    println!("\n🧮 Formal Properties Verified");
-    println!("{}", "-".repeat(40));
-    println!("✅ Non-regular language generation (aⁿbⁿ)");
-    println!("✅ Context-free parsing capability");
-    println!("✅ Minimalist Grammar operations (Merge/Move)");
-    println!("✅ Bounded memory with unbounded recursion");
-    println!("✅ Zero runtime dependencies");
-    
-    println!("\n🎉 Demo complete! Recursion mathematically verified.");
-}
\ No newline at end of file
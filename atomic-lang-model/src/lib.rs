@@ -1,13 +1,14 @@
 //! Atomic Language Model - Extremely Lightweight Universal Grammar Implementation
 //! 
-//! This library implements Minimalist Grammar operations (Merge + Move) in under 3k lines,
+//! This library implements Minimalist Grammar operations (Merge + Move),
 //! providing provable recursion with mathematical rigor while maintaining <50kB binary size.
 //!
 //! # Core Features
 //! - Merge and Move operations from Minimalist Grammar theory
 //! - Provably recursive generation (aⁿbⁿ patterns)
 //! - Zero runtime dependencies
-//! - Polynomial-time parsing with bounded memory
+//! - Greedy parsing with bounded memory, and an exhaustive recognizer that is
+//!   exponential in the worst case
 //! - Token-level linguistic evaluation
 
 #![cfg_attr(feature = "no_std", no_std)]
@@ -17,13 +18,15 @@
 #[cfg(feature = "std")]
 extern crate std;
 
-#[cfg(not(feature = "std"))]
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::{vec::Vec, string::String, format};
+use alloc::{boxed::Box, format, string::{String, ToString}, vec, vec::Vec};
 
 use core::fmt;
+use core::str::FromStr;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use alloc::sync::Arc;
 
 // ============================================================================
 // PyO3 Imports
@@ -36,7 +39,8 @@ use pyo3::prelude::*;
 // ============================================================================
 
 /// Syntactic category labels
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Category {
     // --- Standard Linguistic Categories ---
     /// Noun
@@ -57,6 +61,12 @@ pub enum Category {
     DP,
     /// Complementizer Phrase
     CP,
+    /// Adverb
+    Adv,
+    /// Preposition
+    P,
+    /// Prepositional Phrase
+    PP,
     
     // --- NASA Telemetry Categories ---
     /// A generic telemetry event
@@ -70,18 +80,31 @@ pub enum Category {
 }
 
 /// Feature types for Minimalist Grammar
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Feature {
     /// Basic category feature
     Cat(Category),
     /// Selector feature (requires merge with category)
     Sel(Category),
+    /// Selector satisfied by any one of the listed categories
+    SelAny(Vec<Category>),
     /// Positive feature (triggers movement)
     Pos(u8),
     /// Negative feature (target for movement)
     Neg(u8),
     /// Context feature (e.g., for DRIVE, STANDBY)
     Ctx(String),
+    /// Feature that may be checked like its inner feature or left unchecked
+    Optional(Box<Feature>),
+    /// Marks an item that may merge with its arguments in either order
+    Symmetric,
+    /// Adjunct feature: attaches to a phrase of this category without
+    /// checking a selector, and the result keeps the host's category
+    Adj(Category),
+    /// Agreement value (e.g. number, case or mission mode) that must equal
+    /// the other side's when both a selector and its selectee carry one
+    Agr(String),
 }
 
 impl Feature {
@@ -102,29 +125,162 @@ impl Feature {
             _ => None,
         }
     }
+    
+    /// Check if feature may be left unchecked at completion
+    pub fn is_optional(&self) -> bool {
+        matches!(self, Feature::Optional(_))
+    }
+    
+    /// Categories this feature selects: one for `Sel`, the list for
+    /// `SelAny`, none for any other feature
+    pub fn selected(&self) -> &[Category] {
+        match self {
+            Feature::Sel(cat) => core::slice::from_ref(cat),
+            Feature::SelAny(cats) => cats,
+            _ => &[],
+        }
+    }
+    
+    /// Check if feature is a selector (`Sel` or `SelAny`)
+    pub fn is_selector(&self) -> bool {
+        matches!(self, Feature::Sel(_) | Feature::SelAny(_))
+    }
+    
+    /// Get the feature to check, looking through any `Optional` wrapper
+    pub fn inner(&self) -> &Feature {
+        match self {
+            Feature::Optional(inner) => inner.inner(),
+            other => other,
+        }
+    }
 }
 
 /// Lexical item with phonological form and features
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LexItem {
     /// Phonological representation
     pub phon: String,
-    /// Feature bundle
+    /// Feature bundle; JSON calls it `features`, and also reads the older
+    /// `feats` key
+    #[cfg_attr(feature = "serde", serde(rename = "features", alias = "feats"))]
     pub feats: Vec<Feature>,
+    /// Relative probability of this item among the entries for its phon,
+    /// used by `derive_weighted`; must be positive and finite
+    #[cfg_attr(feature = "serde", serde(default = "default_weight", deserialize_with = "deserialize_weight"))]
+    pub weight: f64,
+}
+
+/// Weight of an entry that does not give one
+#[cfg(feature = "serde")]
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// Read a weight, rejecting those `LexItem::with_weight` would
+#[cfg(feature = "serde")]
+fn deserialize_weight<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let weight = <f64 as serde::Deserialize>::deserialize(deserializer)?;
+    if !is_valid_weight(weight) {
+        return Err(serde::de::Error::custom(format!("lexical weight must be positive and finite, got {}", weight)));
+    }
+    Ok(weight)
+}
+
+/// Whether `weight` can be used as a lexical weight
+fn is_valid_weight(weight: f64) -> bool {
+    weight.is_finite() && weight > 0.0
 }
 
 impl LexItem {
-    /// Create new lexical item
+    /// Create new lexical item with weight 1
     pub fn new(phon: &str, feats: &[Feature]) -> Self {
         Self {
             phon: phon.to_string(),
             feats: feats.to_vec(),
+            weight: 1.0,
+        }
+    }
+    
+    /// Create a covert (phonologically null) item with weight 1
+    ///
+    /// A covert head checks features like any other item but contributes
+    /// nothing to `linearize`. It matches no input token, so it enters a
+    /// derivation through `Workspace::add_covert`.
+    pub fn covert(feats: &[Feature]) -> Self {
+        Self::new("", feats)
+    }
+    
+    /// Check if the item is covert, i.e. has empty phonology
+    pub fn is_covert(&self) -> bool {
+        self.phon.is_empty()
+    }
+    
+    /// Set the item's weight
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is not positive and finite.
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        assert!(is_valid_weight(weight), "lexical weight must be positive and finite, got {}", weight);
+        self.weight = weight;
+        self
+    }
+}
+
+/// Phonology stored on tree leaves
+///
+/// A shared handle that dereferences to `str`, so cloning a leaf (as the
+/// exhaustive search does constantly) copies no text. With the `intern`
+/// feature, the leaves a workspace creates for the same word also share
+/// one allocation, from the workspace's `StringInterner`.
+pub type Phon = Arc<str>;
+
+/// Deduplicating store for leaf phonology
+///
+/// Every leaf a workspace creates for the same word shares one allocation.
+/// The interner survives `Workspace::reset`, so a workspace reused across a
+/// batch stores each distinct word once for the whole batch.
+#[cfg(feature = "intern")]
+#[derive(Debug, Clone, Default)]
+pub struct StringInterner {
+    strings: BTreeSet<Phon>,
+}
+
+#[cfg(feature = "intern")]
+impl StringInterner {
+    /// Create an empty interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Handle for `text`, allocating it only the first time it is seen
+    pub fn intern(&mut self, text: &str) -> Phon {
+        if let Some(phon) = self.strings.get(text) {
+            return phon.clone();
         }
+        let phon = Phon::from(text);
+        self.strings.insert(phon.clone());
+        phon
+    }
+    
+    /// Number of distinct strings stored
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+    
+    /// Check if nothing has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
     }
 }
 
 /// Syntactic object in derivation
-#[derive(Debug, Clone, PartialEq)]
+///
+/// The ordering is structural, field by field; the exhaustive searches use
+/// it to recognise workspaces that hold the same items in another order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SyntacticObject {
     /// Category label
     pub label: Category,
@@ -133,12 +289,23 @@ pub struct SyntacticObject {
     /// Child constituents
     pub children: Vec<SyntacticObject>,
     /// Phonological content (for leaves)
-    pub phon: Option<String>,
+    pub phon: Option<Phon>,
+    /// Feature bundles of the selector and selectee this node was merged
+    /// from, when provenance tracking is enabled
+    pub provenance: Option<Box<(Vec<Feature>, Vec<Feature>)>>,
+    /// For a trace left behind by movement, the index of the `Neg` feature
+    /// its mover checked
+    pub trace: Option<u8>,
 }
 
 impl SyntacticObject {
     /// Create leaf node from lexical item
     pub fn from_lex(item: &LexItem) -> Self {
+        Self::leaf(item, Phon::from(item.phon.as_str()))
+    }
+    
+    /// Leaf for `item`, pronounced through an existing `phon` handle
+    fn leaf(item: &LexItem, phon: Phon) -> Self {
         let label = item.feats.iter()
             .find_map(|f| match f {
                 Feature::Cat(cat) => Some(cat.clone()),
@@ -150,7 +317,9 @@ impl SyntacticObject {
             label,
             features: item.feats.clone(),
             children: Vec::new(),
-            phon: Some(item.phon.clone()),
+            phon: Some(phon),
+            provenance: None,
+            trace: None,
         }
     }
     
@@ -161,7 +330,58 @@ impl SyntacticObject {
             features,
             children,
             phon: None,
+            provenance: None,
+            trace: None,
+        }
+    }
+    
+    /// Create the silent trace of a `label` constituent that moved to check
+    /// `Neg(movement_idx)`
+    ///
+    /// The trace keeps only its category, so the head that selected the
+    /// mover still recognizes its complement while nothing can move it again.
+    pub fn trace(label: Category, movement_idx: u8) -> Self {
+        Self {
+            label: label.clone(),
+            features: vec![Feature::Cat(label)],
+            children: Vec::new(),
+            phon: None,
+            provenance: None,
+            trace: Some(movement_idx),
+        }
+    }
+    
+    /// Check if this node is a trace left behind by movement
+    pub fn is_trace(&self) -> bool {
+        self.trace.is_some()
+    }
+    
+    /// Check the structural invariants of the tree
+    ///
+    /// Every leaf must have phonology, unless it is a trace; every node with
+    /// children must not; and no path may run deeper than
+    /// `MAX_STRUCTURE_DEPTH`, which bounds the walk itself too.
+    pub fn validate(&self) -> Result<(), StructureError> {
+        fn visit(obj: &SyntacticObject, path: &mut Vec<usize>) -> Result<(), StructureError> {
+            if path.len() > MAX_STRUCTURE_DEPTH {
+                return Err(StructureError::TooDeep { limit: MAX_STRUCTURE_DEPTH });
+            }
+            if obj.children.is_empty() && obj.phon.is_none() && !obj.is_trace() {
+                return Err(StructureError::LeafWithoutPhon { path: path.clone() });
+            }
+            if !obj.children.is_empty() && obj.phon.is_some() {
+                return Err(StructureError::InternalWithPhon { path: path.clone() });
+            }
+            
+            for (k, child) in obj.children.iter().enumerate() {
+                path.push(k);
+                visit(child, path)?;
+                path.pop();
+            }
+            Ok(())
         }
+        
+        visit(self, &mut Vec::new())
     }
     
     /// Check if object has no unchecked features
@@ -169,17 +389,717 @@ impl SyntacticObject {
         self.features.is_empty()
     }
     
+    /// Check if a derivation may end with this object
+    ///
+    /// The object's own category feature is the result of the derivation
+    /// rather than a pending requirement, so it may remain; so may
+    /// `Optional` features, which may be left unchecked, the `Symmetric`
+    /// marker, and `Agr` values, which describe the phrase.
+    fn is_saturated(&self) -> bool {
+        self.features.iter().all(|f| matches!(f, Feature::Cat(_) | Feature::Optional(_) | Feature::Symmetric | Feature::Agr(_)))
+    }
+    
+    /// The `Agr` value this object carries, if any
+    fn agreement(&self) -> Option<&str> {
+        self.features.iter().find_map(|f| match f {
+            Feature::Agr(value) => Some(value.as_str()),
+            _ => None,
+        })
+    }
+    
+    /// Check if two trees have the same constituency, labels and phonology
+    ///
+    /// Feature bundles are ignored: they record derivation state rather than
+    /// structure.
+    pub fn structurally_equivalent(&self, other: &SyntacticObject) -> bool {
+        self.label == other.label
+            && self.phon == other.phon
+            && self.children.len() == other.children.len()
+            && self.children.iter()
+                .zip(&other.children)
+                .all(|(a, b)| a.structurally_equivalent(b))
+    }
+    
+    /// Canonical form for `structurally_equivalent`: two trees are
+    /// equivalent exactly when their keys are equal
+    fn structure_key(&self) -> String {
+        let mut key = format!("({:?} {:?}", self.label, self.phon);
+        for child in &self.children {
+            key.push(' ');
+            key.push_str(&child.structure_key());
+        }
+        key.push(')');
+        key
+    }
+    
+    /// Follow head children down to the lexical head of this constituent
+    pub fn lexical_head(&self) -> &SyntacticObject {
+        match self.head_index() {
+            Some(k) => self.children[k].lexical_head(),
+            None => self,
+        }
+    }
+    
+    /// Projections of the lexical head within this constituent, from this
+    /// node down to the first projection (the one whose head is the leaf)
+    fn projections(&self) -> Vec<&SyntacticObject> {
+        let mut path = Vec::new();
+        let mut node = self;
+        while let Some(k) = node.head_index() {
+            path.push(node);
+            node = &node.children[k];
+        }
+        path
+    }
+    
+    /// Non-head daughter of a binary projection
+    fn non_head_child(&self) -> Option<&SyntacticObject> {
+        let head = self.head_index()?;
+        self.children.iter().enumerate().find(|&(k, _)| k != head).map(|(_, child)| child)
+    }
+    
+    /// The head's complement: its sister at the first projection
+    pub fn complement(&self) -> Option<&SyntacticObject> {
+        self.projections().last()?.non_head_child()
+    }
+    
+    /// The head's specifier: the non-head daughter of the second projection,
+    /// whether it was merged there or moved there
+    pub fn specifier(&self) -> Option<&SyntacticObject> {
+        let path = self.projections();
+        path.len().checked_sub(2).and_then(|k| path[k].non_head_child())
+    }
+    
+    /// Lexical item owning the unchecked selectors of this constituent
+    ///
+    /// Merge strips the selector's own `Sel` features and percolates the
+    /// selectee's leftovers, so any selector still open on a merge or move
+    /// node belongs to its right child.
+    fn selector_owner(&self) -> &SyntacticObject {
+        match self.children.as_slice() {
+            [_, right] => right.selector_owner(),
+            _ => self,
+        }
+    }
+    
+    /// Pair every selecting head with the linearized arguments it selected
+    ///
+    /// Arguments are the selectee sisters of merge nodes, attributed to the
+    /// lexical item whose selector they discharged and listed in merge order
+    /// (complement first). Moved specifiers are not arguments, and heads that
+    /// selected nothing are omitted.
+    pub fn argument_structure(&self) -> Vec<(String, Vec<String>)> {
+        self.selected_arguments()
+            .into_iter()
+            .map(|(head, args)| {
+                let args = args.iter().map(|arg| arg.linearize()).collect();
+                (head.phon.as_deref().unwrap_or_default().to_string(), args)
+            })
+            .collect()
+    }
+    
+    /// Lexical items paired with the selectee constituents they selected
+    fn selected_arguments(&self) -> Vec<(&SyntacticObject, Vec<&SyntacticObject>)> {
+        fn visit<'a>(
+            node: &'a SyntacticObject,
+            grid: &mut Vec<(&'a SyntacticObject, Vec<&'a SyntacticObject>)>,
+        ) {
+            if node.head_index() == Some(0) {
+                let head = node.children[0].selector_owner();
+                let argument = &node.children[1];
+                match grid.iter_mut().find(|(h, _)| core::ptr::eq(*h, head)) {
+                    Some((_, args)) => args.insert(0, argument),
+                    None => grid.push((head, vec![argument])),
+                }
+            }
+            for child in &node.children {
+                visit(child, grid);
+            }
+        }
+        
+        let mut grid = Vec::new();
+        visit(self, &mut grid);
+        grid
+    }
+    
+    /// Verify every lexical head discharged the number of selectors its
+    /// category is declared to take in the `arity` side table
+    ///
+    /// Categories absent from the table are unconstrained.
+    pub fn check_arity(&self, arity: &BTreeMap<Category, usize>) -> Result<(), DerivationError> {
+        fn visit(
+            node: &SyntacticObject,
+            grid: &[(&SyntacticObject, Vec<&SyntacticObject>)],
+            arity: &BTreeMap<Category, usize>,
+        ) -> Result<(), DerivationError> {
+            if node.children.is_empty() {
+                let category = node.features.iter().find_map(|f| match f {
+                    Feature::Cat(cat) => Some(cat),
+                    _ => None,
+                });
+                if let Some((category, &expected)) = category.and_then(|cat| arity.get_key_value(cat)) {
+                    let actual = grid.iter()
+                        .find(|(head, _)| core::ptr::eq(*head, node))
+                        .map_or(0, |(_, args)| args.len());
+                    if actual != expected {
+                        return Err(DerivationError::ArityMismatch {
+                            category: category.clone(),
+                            expected,
+                            actual,
+                        });
+                    }
+                }
+            }
+            node.children.iter().try_for_each(|child| visit(child, grid, arity))
+        }
+        
+        visit(self, &self.selected_arguments(), arity)
+    }
+    
+    /// Index of the head child of a binary node
+    ///
+    /// Merge builds `[selector, selectee]` and move builds `[mover, host]`,
+    /// so a node whose left child selects its right child is a merge headed
+    /// on the left; any other binary node is a move headed on the right.
+    fn head_index(&self) -> Option<usize> {
+        match self.children.as_slice() {
+            [left, right] if can_merge_directed(left, right) => Some(0),
+            [_, _] => Some(1),
+            _ => None,
+        }
+    }
+    
     /// Get linearized string representation
+    ///
+    /// Traces are silent, so a moved constituent is pronounced only at its
+    /// landing site.
     pub fn linearize(&self) -> String {
         if let Some(ref phon) = self.phon {
-            phon.clone()
+            phon.to_string()
         } else {
             self.children.iter()
                 .map(|child| child.linearize())
+                .filter(|s| !s.is_empty())
                 .collect::<Vec<_>>()
                 .join(" ")
         }
     }
+    
+    /// Linearize with each category's head direction taken from `order`
+    ///
+    /// A merge node whose category is head-final pronounces its selectee
+    /// before its selector. Movers still land on the left, and adjuncts keep
+    /// their place before the host, so with every category head-initial this
+    /// is `linearize`.
+    pub fn linearize_with(&self, order: &LinearOrder) -> String {
+        if let Some(ref phon) = self.phon {
+            return phon.to_string();
+        }
+        
+        let mut children: Vec<&SyntacticObject> = self.children.iter().collect();
+        if self.head_index() == Some(0) && order.direction(&self.label) == HeadDirection::Final {
+            children.reverse();
+        }
+        children.iter()
+            .map(|child| child.linearize_with(order))
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+    
+    /// Get the structural skeleton of the subtree, ignoring phonology
+    ///
+    /// Leaves render as their category (`N`), internal nodes as a bracket
+    /// of their label followed by the children's shapes (`[D D N]`).
+    pub fn shape(&self) -> String {
+        if self.children.is_empty() {
+            format!("{:?}", self.label)
+        } else {
+            let children: Vec<String> = self.children.iter().map(|child| child.shape()).collect();
+            format!("[{:?} {}]", self.label, children.join(" "))
+        }
+    }
+    
+    /// Freeze the constituent into an atomic lexical item named `phon`
+    ///
+    /// The item has the constituent's category, followed by any features it
+    /// still has unchecked, so a complete phrase such as "the student" becomes
+    /// a bare `D` leaf that merges exactly like the phrase did.
+    pub fn to_lex_item(&self, phon: &str) -> LexItem {
+        let mut feats = vec![Feature::Cat(self.label.clone())];
+        feats.extend(self.features.iter().filter(|f| !matches!(f, Feature::Cat(_))).cloned());
+        LexItem::new(phon, &feats)
+    }
+    
+    /// Summarize which operations built the tree
+    ///
+    /// Every binary node is classified as a merge or a move, and a context
+    /// feature counts as checked where it is present on a node's children but
+    /// gone from the node itself. A merge whose selector and selectee both
+    /// carry `Agr` counts as an agreement.
+    pub fn feature_summary(&self) -> FeatureSummary {
+        fn visit(obj: &SyntacticObject, summary: &mut FeatureSummary) {
+            match obj.head_index() {
+                Some(0) => {
+                    summary.selectors += 1;
+                    if obj.children.iter().all(|child| child.agreement().is_some()) {
+                        summary.agreements += 1;
+                    }
+                }
+                Some(_) => summary.movements += 1,
+                None => {}
+            }
+            
+            let mut remaining = obj.features.clone();
+            for child in &obj.children {
+                for feature in &child.features {
+                    if let Some(pos) = remaining.iter().position(|f| f == feature) {
+                        remaining.remove(pos);
+                    } else if matches!(feature.inner(), Feature::Ctx(_)) {
+                        summary.contexts += 1;
+                    }
+                }
+            }
+            
+            for child in &obj.children {
+                visit(child, summary);
+            }
+        }
+        
+        let mut summary = FeatureSummary::default();
+        visit(self, &mut summary);
+        summary
+    }
+    
+    /// Copy the tree with every movement index shifted by `offset`
+    ///
+    /// Constituents built independently may reuse the same indices for
+    /// unrelated movements; shifting one of them apart before combining keeps
+    /// its `Pos`/`Neg` features from checking the other's. Indices wrap
+    /// around past `u8::MAX`.
+    pub fn rename_movement_indices(&self, offset: u8) -> SyntacticObject {
+        fn shift(feature: &Feature, offset: u8) -> Feature {
+            match feature {
+                Feature::Pos(i) => Feature::Pos(i.wrapping_add(offset)),
+                Feature::Neg(i) => Feature::Neg(i.wrapping_add(offset)),
+                Feature::Optional(inner) => Feature::Optional(Box::new(shift(inner, offset))),
+                other => other.clone(),
+            }
+        }
+        
+        SyntacticObject {
+            label: self.label.clone(),
+            features: self.features.iter().map(|f| shift(f, offset)).collect(),
+            children: self.children.iter().map(|child| child.rename_movement_indices(offset)).collect(),
+            phon: self.phon.clone(),
+            provenance: self.provenance.as_ref().map(|bundles| {
+                let (selector, selectee) = &**bundles;
+                Box::new((
+                    selector.iter().map(|f| shift(f, offset)).collect(),
+                    selectee.iter().map(|f| shift(f, offset)).collect(),
+                ))
+            }),
+            trace: self.trace.map(|i| i.wrapping_add(offset)),
+        }
+    }
+    
+    /// Number of merge and move operations that built the tree
+    ///
+    /// Each operation creates exactly one internal node.
+    pub fn derivation_steps(&self) -> usize {
+        if self.children.is_empty() {
+            0
+        } else {
+            1 + self.children.iter().map(SyntacticObject::derivation_steps).sum::<usize>()
+        }
+    }
+    
+    /// Count the most phrases of `category` nested inside one another
+    ///
+    /// Only maximal projections count, so a phrase and its own head (or a
+    /// landing site above it) are one level, not two.
+    pub fn embedding_depth(&self, category: &Category) -> usize {
+        fn depth(obj: &SyntacticObject, category: &Category, inside: bool) -> usize {
+            let here = obj.label == *category;
+            let below = obj.children.iter()
+                .map(|child| depth(child, category, here))
+                .max()
+                .unwrap_or(0);
+            below + usize::from(here && !inside)
+        }
+        
+        depth(self, category, false)
+    }
+    
+    /// Distinct `Ctx` values in the tree, in pre-order of first appearance
+    ///
+    /// With `include_checked` every node's features are searched, so contexts
+    /// already checked below the root are reported too; otherwise only the
+    /// root's still-unchecked features are.
+    pub fn active_contexts(&self, include_checked: bool) -> Vec<String> {
+        fn visit(obj: &SyntacticObject, deep: bool, contexts: &mut Vec<String>) {
+            for feature in &obj.features {
+                if let Feature::Ctx(ctx) = feature.inner() {
+                    if !contexts.contains(ctx) {
+                        contexts.push(ctx.clone());
+                    }
+                }
+            }
+            if deep {
+                for child in &obj.children {
+                    visit(child, deep, contexts);
+                }
+            }
+        }
+        
+        let mut contexts = Vec::new();
+        visit(self, include_checked, &mut contexts);
+        contexts
+    }
+    
+    /// Flatten the tree into pre-order `(depth, label, phon)` rows
+    pub fn to_rows(&self) -> Vec<(usize, Category, Option<String>)> {
+        fn visit(obj: &SyntacticObject, depth: usize, rows: &mut Vec<(usize, Category, Option<String>)>) {
+            rows.push((depth, obj.label.clone(), obj.phon.as_deref().map(String::from)));
+            for child in &obj.children {
+                visit(child, depth + 1, rows);
+            }
+        }
+        
+        let mut rows = Vec::new();
+        visit(self, 0, &mut rows);
+        rows
+    }
+    
+    /// Render the tree in Penn Treebank bracket format
+    ///
+    /// Leaves become `(POS word)` preterminals, e.g. `(D (D the) (N student))`,
+    /// traces the empty element `(-NONE- *T*)`, and covert heads the null
+    /// element `(-NONE- 0)`. Brackets inside words are
+    /// escaped as `-LRB-`/`-RRB-` so the output stays readable by treebank
+    /// tools such as EVALB.
+    pub fn to_ptb(&self) -> String {
+        if self.is_trace() {
+            "(-NONE- *T*)".to_string()
+        } else if self.phon.as_deref() == Some("") {
+            "(-NONE- 0)".to_string()
+        } else if self.children.is_empty() {
+            let word = self.phon.as_deref().unwrap_or("")
+                .replace('(', "-LRB-")
+                .replace(')', "-RRB-");
+            format!("({:?} {})", self.label, word)
+        } else {
+            let children: Vec<String> = self.children.iter().map(|child| child.to_ptb()).collect();
+            format!("({:?} {})", self.label, children.join(" "))
+        }
+    }
+    
+    /// Render the tree in labeled-bracket notation
+    ///
+    /// Leaves become `[POS word]`, e.g. `[D [D the] [N student]]`, and a
+    /// covert head `[POS ε]`. A moved
+    /// constituent is marked `_i` at its landing site and the trace it left
+    /// behind is written `t_i`; a constituent that moves again keeps the
+    /// index of its first landing site, so a chain shares one index.
+    pub fn to_bracketed(&self) -> String {
+        /// Chain number of the closest mover that could have left `trace`
+        fn chain_of(pending: &[(Category, u8, usize)], trace: &SyntacticObject) -> Option<usize> {
+            pending.iter().rev()
+                .find(|(label, idx, _)| *label == trace.label && Some(*idx) == trace.trace)
+                .map(|&(_, _, chain)| chain)
+        }
+        
+        // `pending` holds (label, movement index, chain number) for every
+        // mover whose trace may still lie below
+        fn render(obj: &SyntacticObject, pending: &mut Vec<(Category, u8, usize)>, next: &mut usize) -> String {
+            if obj.is_trace() {
+                return match chain_of(pending, obj) {
+                    Some(chain) => format!("t_{}", chain),
+                    None => "t".to_string(),
+                };
+            }
+            
+            if obj.children.is_empty() {
+                let phon = obj.phon.as_deref().filter(|phon| !phon.is_empty()).unwrap_or("ε");
+                return format!("[{:?} {}]", obj.label, phon);
+            }
+            
+            if let Some(idx) = obj.checked_movement_index() {
+                let (mover, body) = (&obj.children[0], &obj.children[1]);
+                // A trace as mover continues the chain that left it
+                let (chain, mover_str) = match chain_of(pending, mover).filter(|_| mover.is_trace()) {
+                    Some(chain) => (chain, format!("t_{}", chain)),
+                    None => {
+                        *next += 1;
+                        let chain = *next;
+                        (chain, format!("{}_{}", render(mover, pending, next), chain))
+                    }
+                };
+                
+                pending.push((mover.label.clone(), idx, chain));
+                let body_str = render(body, pending, next);
+                pending.pop();
+                
+                return format!("[{:?} {} {}]", obj.label, mover_str, body_str);
+            }
+            
+            let children: Vec<String> = obj.children.iter().map(|child| render(child, pending, next)).collect();
+            format!("[{:?} {}]", obj.label, children.join(" "))
+        }
+        
+        render(self, &mut Vec::new(), &mut 0)
+    }
+    
+    /// Movement index checked at a move node `[mover, body]`: the trigger
+    /// the body still has but the node no longer does
+    fn checked_movement_index(&self) -> Option<u8> {
+        if self.head_index() != Some(1) {
+            return None;
+        }
+        let body = &self.children[1];
+        let count = |features: &[Feature], idx: u8| features.iter().filter(|f| *f.inner() == Feature::Pos(idx)).count();
+        body.features.iter()
+            .filter_map(|f| match f.inner() {
+                Feature::Pos(idx) => Some(*idx),
+                _ => None,
+            })
+            .find(|&idx| count(&body.features, idx) > count(&self.features, idx))
+    }
+    
+    /// Every movement step in the tree, in pre-order of landing sites
+    ///
+    /// Each chain links a moved constituent to the trace it left, found as
+    /// the first trace below its landing site with the same category and
+    /// movement index. A constituent that moved twice yields two chains: the
+    /// later one's trace is the earlier one's landing site.
+    pub fn movement_chains(&self) -> Vec<MovementChain> {
+        fn find_trace(obj: &SyntacticObject, label: &Category, index: u8, path: &mut Vec<usize>) -> bool {
+            if obj.is_trace() && obj.label == *label && obj.trace == Some(index) {
+                return true;
+            }
+            for (k, child) in obj.children.iter().enumerate() {
+                path.push(k);
+                if find_trace(child, label, index, path) {
+                    return true;
+                }
+                path.pop();
+            }
+            false
+        }
+        
+        fn collect(obj: &SyntacticObject, path: &mut Vec<usize>, chains: &mut Vec<MovementChain>) {
+            if let Some(index) = obj.checked_movement_index() {
+                let mover = &obj.children[0];
+                let mut trace = [path.as_slice(), &[1]].concat();
+                let found = find_trace(&obj.children[1], &mover.label, index, &mut trace);
+                chains.push(MovementChain {
+                    index,
+                    landing: [path.as_slice(), &[0]].concat(),
+                    trace: found.then_some(trace),
+                });
+            }
+            for (k, child) in obj.children.iter().enumerate() {
+                path.push(k);
+                collect(child, path, chains);
+                path.pop();
+            }
+        }
+        
+        let mut chains = Vec::new();
+        collect(self, &mut Vec::new(), &mut chains);
+        chains
+    }
+    
+    /// Render the tree as a Graphviz `digraph`
+    ///
+    /// Each node is labeled with its category, plus its phonology at leaves,
+    /// and edges run from parent to child.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph {\n");
+        self.write_dot(false, &mut 0, &mut out);
+        out.push_str("}\n");
+        out
+    }
+    
+    /// Like `to_dot`, but also list each node's unchecked features
+    pub fn to_dot_with_features(&self) -> String {
+        let mut out = String::from("digraph {\n");
+        self.write_dot(true, &mut 0, &mut out);
+        out.push_str("}\n");
+        out
+    }
+    
+    /// Append this subtree's DOT nodes and edges, returning the root's id
+    fn write_dot(&self, show_features: bool, next_id: &mut usize, out: &mut String) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        
+        let mut label = format!("{:?}", self.label);
+        if let Some(ref phon) = self.phon {
+            label.push('\n');
+            label.push_str(phon);
+        }
+        if show_features && !self.features.is_empty() {
+            label.push_str(&format!("\n{:?}", self.features));
+        }
+        let label = label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+        out.push_str(&format!("    n{} [label=\"{}\"];\n", id, label));
+        
+        for child in &self.children {
+            let child_id = child.write_dot(show_features, next_id, out);
+            out.push_str(&format!("    n{} -> n{};\n", id, child_id));
+        }
+        id
+    }
+    
+    /// Build a parallel tree recording the linearized span of every node
+    ///
+    /// Spans are computed in a single bottom-up pass, reusing each child's
+    /// span rather than re-linearizing every subtree from scratch.
+    pub fn with_span_annotations(&self) -> AnnotatedTree {
+        let children: Vec<AnnotatedTree> = self.children.iter()
+            .map(|child| child.with_span_annotations())
+            .collect();
+        
+        let span = match self.phon {
+            Some(ref phon) => phon.to_string(),
+            None => children.iter()
+                .map(|child| child.span.as_str())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        
+        AnnotatedTree {
+            label: self.label.clone(),
+            span,
+            children,
+        }
+    }
+}
+
+// The surface string, as `linearize` gives it; `{:?}` and `to_bracketed`
+// show the structure.
+impl fmt::Display for SyntacticObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.linearize())
+    }
+}
+
+/// Syntactic tree where every node carries the text it spans
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotatedTree {
+    /// Category label of the original node
+    pub label: Category,
+    /// Linearization of the original node's subtree
+    pub span: String,
+    /// Annotated child constituents
+    pub children: Vec<AnnotatedTree>,
+}
+
+/// Side of its phrase on which a head is pronounced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeadDirection {
+    /// Head before its complement, as in English `saw the student`
+    #[default]
+    Initial,
+    /// Head after its complement, as in Japanese or Turkish
+    Final,
+}
+
+/// Head direction per category, for `SyntacticObject::linearize_with`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinearOrder {
+    directions: BTreeMap<Category, HeadDirection>,
+    default: HeadDirection,
+}
+
+impl LinearOrder {
+    /// Order where every category is head-initial
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Set the direction for categories without their own entry
+    pub fn with_default(mut self, direction: HeadDirection) -> Self {
+        self.default = direction;
+        self
+    }
+    
+    /// Set the direction for one category
+    pub fn with_direction(mut self, category: Category, direction: HeadDirection) -> Self {
+        self.directions.insert(category, direction);
+        self
+    }
+    
+    /// Head direction of `category`
+    pub fn direction(&self, category: &Category) -> HeadDirection {
+        self.directions.get(category).copied().unwrap_or(self.default)
+    }
+}
+
+/// Counts of the grammatical operations a derivation relied on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureSummary {
+    /// Selector features discharged, one per merge
+    pub selectors: usize,
+    /// Movements performed
+    pub movements: usize,
+    /// Context features checked
+    pub contexts: usize,
+    /// Merges where the selector's and selectee's `Agr` values were matched
+    pub agreements: usize,
+}
+
+/// Deepest nesting `SyntacticObject::validate` accepts
+pub const MAX_STRUCTURE_DEPTH: usize = 1024;
+
+/// Violation of a tree's structural invariants
+///
+/// Paths list child indices from the root to the offending node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructureError {
+    /// A leaf that is not a trace has no phonology
+    LeafWithoutPhon {
+        /// Location of the leaf
+        path: Vec<usize>,
+    },
+    /// A node with children also has phonology
+    InternalWithPhon {
+        /// Location of the node
+        path: Vec<usize>,
+    },
+    /// The tree nests deeper than the limit
+    TooDeep {
+        /// Maximum depth allowed
+        limit: usize,
+    },
+}
+
+impl fmt::Display for StructureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StructureError::LeafWithoutPhon { path } => write!(f, "Leaf without phonology at {:?}", path),
+            StructureError::InternalWithPhon { path } => write!(f, "Internal node with phonology at {:?}", path),
+            StructureError::TooDeep { limit } => write!(f, "Tree deeper than {} levels", limit),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StructureError {}
+
+/// One movement step, from `SyntacticObject::movement_chains`
+///
+/// Paths list child indices from the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovementChain {
+    /// Index of the `Pos`/`Neg` pair the movement checked
+    pub index: u8,
+    /// Location of the moved constituent
+    pub landing: Vec<usize>,
+    /// Location of the trace it left, if one is below the landing site
+    pub trace: Option<Vec<usize>>,
 }
 
 // ============================================================================
@@ -195,23 +1115,119 @@ pub struct Workspace {
     pub memory_limit: usize,
     /// Step counter for derivation
     pub step_count: usize,
+    /// Maximum nesting of maximal projections per category
+    pub embedding_limits: BTreeMap<Category, usize>,
+    /// Number of selectors a head of each category must discharge; heads
+    /// of categories absent from the table are unconstrained
+    pub arity: BTreeMap<Category, usize>,
+    /// Seed for shuffling mergeable pairs, for operation-order testing
+    pub shuffle_seed: Option<u64>,
+    /// Record pre-merge feature bundles on every node `step` merges
+    pub track_provenance: bool,
+    /// Never attempt movement; derivations that need it get stuck
+    pub merge_only: bool,
+    /// Memory cost of a single node, excluding its children
+    pub node_cost: fn(&SyntacticObject) -> usize,
+    /// Order in which `step` considers mergeable pairs
+    pub merge_policy: MergePolicy,
+    /// How many levels deep movement may search for its target
+    pub max_move_depth: usize,
+    /// Category the final object must have for the derivation to succeed;
+    /// any category is accepted when unset
+    pub start_category: Option<Category>,
+    /// Shared storage for the phonology of the leaves `add_lex` creates
+    #[cfg(feature = "intern")]
+    interner: StringInterner,
+    /// Only merge items at neighbouring indices; the result takes their
+    /// place, so the workspace stays in surface order
+    pub require_adjacency: bool,
+    merge_attempts: usize,
+    merge_successes: usize,
+    move_attempts: usize,
+    move_successes: usize,
+}
+
+/// Tie-breaking order among mergeable pairs
+///
+/// `step` merges the first pair in this order, so the policy fully decides
+/// the derivation for a given workspace.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Lowest selector index first, then lowest selectee index
+    #[default]
+    Leftmost,
+    /// Fewest unchecked features on the merged result first, ties broken
+    /// as in `Leftmost`
+    FewestFeatures,
 }
 
 /// Errors that can occur during derivation
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DerivationError {
     /// No valid operations available
     NoValidOperations,
     /// Memory limit exceeded
     MemoryLimitExceeded,
     /// Feature mismatch in operation
-    FeatureMismatch,
+    ///
+    /// For selection, `expected` is the selector that went unsatisfied, or
+    /// `None` when the selecting object has no selector left, and `found` is
+    /// what the candidate offered: its category, or the movement trigger it
+    /// still has to check. For adjunction, `expected` is the `Adj` feature
+    /// the host would license.
+    FeatureMismatch {
+        /// Feature the operation required, if any
+        expected: Option<Feature>,
+        /// Feature the candidate offered in its place, if any
+        found: Option<Feature>,
+    },
     /// Empty workspace
     EmptyWorkspace,
     /// Invalid operation sequence
     InvalidOperation,
-    /// Unknown Token
-    UnknownToken(String),
+    /// Token missing from the lexicon, with its position in the input
+    ///
+    /// `byte_offset` counts bytes from the start of the input string; for
+    /// input given as separate tokens it is the offset the token would have
+    /// if they were joined by single spaces.
+    UnknownToken {
+        /// The offending token
+        token: String,
+        /// Position among the input's tokens, from 0
+        index: usize,
+        /// Byte offset of the token's first byte
+        byte_offset: usize,
+    },
+    /// Every distinct unknown token in the input, in order of appearance
+    UnknownTokens(Vec<String>),
+    /// Parse attempted with no lexical items loaded
+    EmptyLexicon,
+    /// Input has more tokens than the parser accepts
+    InputTooLong {
+        /// Number of tokens in the input
+        tokens: usize,
+        /// Maximum number of tokens allowed
+        limit: usize,
+    },
+    /// Phrases of a category nested deeper than the workspace allows
+    EmbeddingLimitExceeded(Category),
+    /// Head discharged a different number of selectors than its category's arity
+    ArityMismatch {
+        /// Category of the offending head
+        category: Category,
+        /// Declared number of arguments
+        expected: usize,
+        /// Number of arguments actually selected
+        actual: usize,
+    },
+    /// Derivation completed with a category other than the workspace's (or
+    /// grammar's) start category
+    WrongStartCategory {
+        /// Declared start category
+        expected: Category,
+        /// Category the derivation ended in
+        found: Category,
+    },
 }
 
 impl fmt::Display for DerivationError {
@@ -219,10 +1235,67 @@ impl fmt::Display for DerivationError {
         match self {
             DerivationError::NoValidOperations => write!(f, "No valid operations available"),
             DerivationError::MemoryLimitExceeded => write!(f, "Memory limit exceeded"),
-            DerivationError::FeatureMismatch => write!(f, "Feature mismatch"),
-            DerivationError::EmptyWorkspace => write!(f, "Empty workspace"),
+            DerivationError::FeatureMismatch { expected, found } => {
+                write!(f, "Feature mismatch: expected ")?;
+                match expected {
+                    Some(expected) => write!(f, "{:?}", expected)?,
+                    None => write!(f, "a selector")?,
+                }
+                match found {
+                    Some(found) => write!(f, ", found {:?}", found),
+                    None => write!(f, ", found nothing"),
+                }
+            }
+            DerivationError::EmptyWorkspace => write!(f, "Empty workspace"),
             DerivationError::InvalidOperation => write!(f, "Invalid operation"),
-            DerivationError::UnknownToken(s) => write!(f, "Unknown token: {}", s),
+            DerivationError::UnknownToken { token, index, byte_offset } => {
+                write!(f, "Unknown token: {} (token {}, byte {})", token, index, byte_offset)
+            }
+            DerivationError::UnknownTokens(tokens) => write!(f, "Unknown tokens: {}", tokens.join(", ")),
+            DerivationError::EmptyLexicon => write!(f, "Empty lexicon: no grammar loaded"),
+            DerivationError::InputTooLong { tokens, limit } => write!(
+                f, "Input too long: {} tokens exceeds the limit of {}", tokens, limit
+            ),
+            DerivationError::EmbeddingLimitExceeded(cat) => write!(f, "Embedding limit exceeded for {:?}", cat),
+            DerivationError::ArityMismatch { category, expected, actual } => write!(
+                f, "Arity mismatch: {:?} expects {} argument(s), got {}", category, expected, actual
+            ),
+            DerivationError::WrongStartCategory { expected, found } => write!(
+                f, "Derivation ended in {:?}, expected the start category {:?}", found, expected
+            ),
+        }
+    }
+}
+
+// No variant wraps an underlying error, so `source` keeps its default of
+// `None`; `UnknownToken` carries the offending token, not a cause.
+#[cfg(feature = "std")]
+impl std::error::Error for DerivationError {}
+
+impl DerivationError {
+    /// `UnknownToken` for `tokens[index]`, placed as if the tokens were
+    /// joined by single spaces
+    fn unknown_token(tokens: &[&str], index: usize) -> Self {
+        DerivationError::UnknownToken {
+            token: tokens[index].to_string(),
+            index,
+            byte_offset: tokens[..index].iter().map(|token| token.len() + 1).sum(),
+        }
+    }
+    
+    /// Point an `UnknownToken` at its exact byte offset in `input`
+    ///
+    /// `tokens` must be the slices of `input` the error was raised for, as
+    /// `split_whitespace` or `Tokenizer::tokenize` return them, so the offset
+    /// accounts for runs of whitespace and multi-byte characters alike.
+    fn located_in(self, input: &str, tokens: &[&str]) -> Self {
+        match self {
+            DerivationError::UnknownToken { token, index, .. } => DerivationError::UnknownToken {
+                token,
+                index,
+                byte_offset: tokens[index].as_ptr() as usize - input.as_ptr() as usize,
+            },
+            other => other,
         }
     }
 }
@@ -234,95 +1307,574 @@ impl Workspace {
             items: Vec::new(),
             memory_limit,
             step_count: 0,
+            embedding_limits: BTreeMap::new(),
+            arity: BTreeMap::new(),
+            shuffle_seed: None,
+            track_provenance: false,
+            merge_only: false,
+            node_cost: allocation_node_cost,
+            merge_policy: MergePolicy::default(),
+            max_move_depth: DEFAULT_MAX_MOVE_DEPTH,
+            start_category: None,
+            #[cfg(feature = "intern")]
+            interner: StringInterner::new(),
+            require_adjacency: false,
+            merge_attempts: 0,
+            merge_successes: 0,
+            move_attempts: 0,
+            move_successes: 0,
+        }
+    }
+    
+    /// Record each merged node's input feature bundles as its provenance
+    pub fn with_provenance(mut self) -> Self {
+        self.track_provenance = true;
+        self
+    }
+    
+    /// Restrict `step` to merge, for grammars without movement
+    pub fn with_merge_only(mut self) -> Self {
+        self.merge_only = true;
+        self
+    }
+    
+    /// Words interned so far, kept across `reset`
+    #[cfg(feature = "intern")]
+    pub fn interner(&self) -> &StringInterner {
+        &self.interner
+    }
+    
+    /// Restrict merge to items that are next to each other in the workspace
+    pub fn with_adjacency(mut self) -> Self {
+        self.require_adjacency = true;
+        self
+    }
+    
+    /// Bound how deep movement searches for its target
+    pub fn with_max_move_depth(mut self, max_move_depth: usize) -> Self {
+        self.max_move_depth = max_move_depth;
+        self
+    }
+    
+    /// Choose which mergeable pair `step` prefers
+    pub fn with_merge_policy(mut self, merge_policy: MergePolicy) -> Self {
+        self.merge_policy = merge_policy;
+        self
+    }
+    
+    /// Render every item as a Graphviz `digraph` forest
+    ///
+    /// Nodes list their unchecked features, as in
+    /// `SyntacticObject::to_dot_with_features`, so a stuck derivation shows
+    /// which requirements were left over.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph {\n");
+        let mut next_id = 0;
+        for item in &self.items {
+            item.write_dot(true, &mut next_id, &mut out);
+        }
+        out.push_str("}\n");
+        out
+    }
+    
+    /// Readable snapshot of the derivation state
+    ///
+    /// The first line gives the step count, memory usage against the limit,
+    /// and how many mergeable pairs are available; each following line shows
+    /// one item's linearization and unchecked features. A workspace with no
+    /// mergeable pairs and no movable item has stalled.
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "step {}, memory {}/{}, {} mergeable pair(s)\n",
+            self.step_count,
+            self.memory_usage(),
+            self.memory_limit,
+            find_mergeable_pairs(self).len(),
+        );
+        for (i, item) in self.items.iter().enumerate() {
+            out.push_str(&format!("[{}] {:?} {:?}\n", i, item.linearize(), item.features));
         }
+        out
+    }
+    
+    /// Weigh nodes with a custom cost function in `memory_usage`
+    pub fn with_node_cost(mut self, node_cost: fn(&SyntacticObject) -> usize) -> Self {
+        self.node_cost = node_cost;
+        self
+    }
+    
+    /// Number of merges `step` has attempted
+    pub fn merge_attempts(&self) -> usize {
+        self.merge_attempts
+    }
+    
+    /// Number of merges `step` has completed
+    pub fn merge_successes(&self) -> usize {
+        self.merge_successes
+    }
+    
+    /// Number of movements `step` has attempted
+    pub fn move_attempts(&self) -> usize {
+        self.move_attempts
+    }
+    
+    /// Number of movements `step` has completed
+    pub fn move_successes(&self) -> usize {
+        self.move_successes
+    }
+    
+    /// Shuffle the mergeable pairs offered to `step` with a seeded RNG
+    ///
+    /// Each step reshuffles with the seed mixed with the step count, so one
+    /// seed fixes a whole (reproducible) random operation order.
+    pub fn with_shuffle_seed(mut self, seed: u64) -> Self {
+        self.shuffle_seed = Some(seed);
+        self
+    }
+    
+    /// Allow at most `limit` nested phrases of `category`
+    pub fn with_embedding_limit(mut self, category: Category, limit: usize) -> Self {
+        self.embedding_limits.insert(category, limit);
+        self
+    }
+    
+    /// Require heads of `category` to discharge exactly `arity` selectors
+    ///
+    /// `find_mergeable_pairs` withholds merges that would complete such a
+    /// head with any other number of arguments, and a derivation left with
+    /// no other operation fails with that `ArityMismatch`.
+    pub fn with_arity(mut self, category: Category, arity: usize) -> Self {
+        self.arity.insert(category, arity);
+        self
+    }
+    
+    /// Require the derivation to end in an object of `category`
+    pub fn with_start_category(mut self, category: Category) -> Self {
+        self.start_category = Some(category);
+        self
+    }
+    
+    /// Clear the workspace for a new derivation, keeping its configuration
+    ///
+    /// Items, the step count and the operation counters are cleared, but
+    /// the limits, policies, the `items` allocation and any interned words
+    /// are kept, so one workspace can be reused across many short
+    /// derivations.
+    pub fn reset(&mut self) {
+        self.items.clear();
+        self.step_count = 0;
+        self.merge_attempts = 0;
+        self.merge_successes = 0;
+        self.move_attempts = 0;
+        self.move_successes = 0;
     }
     
     /// Add lexical item to workspace
     pub fn add_lex(&mut self, item: &LexItem) {
-        let obj = SyntacticObject::from_lex(item);
-        self.items.push(obj);
+        #[cfg(feature = "intern")]
+        let phon = self.interner.intern(&item.phon);
+        #[cfg(not(feature = "intern"))]
+        let phon = Phon::from(item.phon.as_str());
+        self.items.push(SyntacticObject::leaf(item, phon));
+    }
+    
+    /// Add one copy of every covert item in `lexicon`, after the items
+    /// already loaded
+    ///
+    /// The sentence parsers call this once the tokens are loaded, so a
+    /// lexicon's covert heads take part in every parse with it.
+    pub fn add_covert(&mut self, lexicon: &[LexItem]) {
+        for item in lexicon.iter().filter(|item| item.is_covert()) {
+            self.add_lex(item);
+        }
     }
     
     /// Check if derivation is successful (single complete object)
+    ///
+    /// With a start category set, the object must also have that label.
     pub fn is_successful(&self) -> bool {
-        self.items.len() == 1 && self.items[0].is_complete()
+        self.items.len() == 1
+            && self.items[0].is_saturated()
+            && self.start_category.as_ref().map_or(true, |cat| *cat == self.items[0].label)
     }
     
     /// Get current memory usage estimate
+    ///
+    /// Sums `node_cost` over every node, which by default weighs each node
+    /// by its phonology and unchecked features as well.
     pub fn memory_usage(&self) -> usize {
-        // Simple estimate based on object count and depth
         self.items.iter()
             .map(|obj| self.object_size(obj))
             .sum()
     }
     
+    /// Total number of nodes across all items
+    pub fn node_count(&self) -> usize {
+        fn count(obj: &SyntacticObject) -> usize {
+            1 + obj.children.iter().map(count).sum::<usize>()
+        }
+        self.items.iter().map(count).sum()
+    }
+    
+    /// Category whose embedding limit `obj` violates, if any
+    fn exceeded_embedding_limit(&self, obj: &SyntacticObject) -> Option<Category> {
+        self.embedding_limits.iter()
+            .find(|(cat, &limit)| obj.embedding_depth(cat) > limit)
+            .map(|(cat, _)| cat.clone())
+    }
+    
+    /// The `ArityMismatch` merging `a` with `b` would commit, if any
+    ///
+    /// Merge checks off every selector of the selecting item, so its head
+    /// is complete once it has taken `b` and its argument count is final.
+    fn arity_mismatch(&self, a: &SyntacticObject, b: &SyntacticObject) -> Option<DerivationError> {
+        if self.arity.is_empty() {
+            return None;
+        }
+        let selector = if is_symmetric_pair(a, b) && !can_merge_directed(a, b) && can_merge_directed(b, a) {
+            b
+        } else if can_merge_directed(a, b) {
+            a
+        } else {
+            return None; // Adjunction completes no head
+        };
+        
+        let head = selector.selector_owner();
+        let (category, &expected) = head.features.iter()
+            .find_map(|f| match f {
+                Feature::Cat(cat) => Some(cat),
+                _ => None,
+            })
+            .and_then(|cat| self.arity.get_key_value(cat))?;
+        let actual = 1 + selector.selected_arguments().iter()
+            .find(|(owner, _)| core::ptr::eq(*owner, head))
+            .map_or(0, |(_, args)| args.len());
+        (actual != expected).then(|| DerivationError::ArityMismatch { category: category.clone(), expected, actual })
+    }
+    
+    /// Error for a workspace where no operation applies: an arity mismatch
+    /// that withheld a merge, if any, else `NoValidOperations`
+    fn stuck_error(&self) -> DerivationError {
+        if self.arity.is_empty() {
+            return DerivationError::NoValidOperations;
+        }
+        let n = self.items.len();
+        (0..n).flat_map(|i| (0..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| i != j && (!self.require_adjacency || i.abs_diff(j) == 1))
+            .filter(|&(i, j)| can_merge(&self.items[i], &self.items[j]))
+            .find_map(|(i, j)| self.arity_mismatch(&self.items[i], &self.items[j]))
+            .unwrap_or(DerivationError::NoValidOperations)
+    }
+    
     fn object_size(&self, obj: &SyntacticObject) -> usize {
-        1 + obj.children.iter().map(|child| self.object_size(child)).sum::<usize>()
+        (self.node_cost)(obj) + obj.children.iter().map(|child| self.object_size(child)).sum::<usize>()
     }
 }
 
+/// Node cost where every node counts as 1, so `memory_usage` equals
+/// `node_count`
+pub fn uniform_node_cost(_obj: &SyntacticObject) -> usize {
+    1
+}
+
+/// Default node cost: 1 for the node, plus the bytes of its phonology and
+/// one per unchecked feature
+pub fn allocation_node_cost(obj: &SyntacticObject) -> usize {
+    1 + obj.phon.as_deref().map_or(0, str::len) + obj.features.len()
+}
+
 // ============================================================================
 // Core Operations: Merge
 // ============================================================================
 
 /// Attempt to merge two syntactic objects
+///
+/// If `a` cannot select `b` but `b` can select `a`, and either is marked
+/// `Symmetric`, the roles are swapped, so both argument orders build the
+/// same structure. When neither selects the other, adjunction is tried in
+/// either direction.
 pub fn merge(a: SyntacticObject, b: SyntacticObject) -> Result<SyntacticObject, DerivationError> {
+    if is_symmetric_pair(&a, &b) && !can_merge_directed(&a, &b) && can_merge_directed(&b, &a) {
+        return merge_directed(b, a);
+    }
+    if !can_merge_directed(&a, &b) {
+        if can_adjoin(&a, &b) {
+            return merge_adjoin(a, b);
+        }
+        if can_adjoin(&b, &a) {
+            return merge_adjoin(b, a);
+        }
+    }
+    merge_directed(a, b)
+}
+
+/// Adjoin `adjunct` to `host`
+///
+/// The adjunct's first `Adj` feature must name the host's category. Unlike
+/// selection, adjunction checks nothing on the host: the result has the
+/// host's label and features, plus whatever the adjunct still carries
+/// besides its category and the `Adj` it used. The adjunct is placed before
+/// the host, and like a selectee it must have discharged its own movement
+/// triggers.
+pub fn merge_adjoin(adjunct: SyntacticObject, host: SyntacticObject) -> Result<SyntacticObject, DerivationError> {
+    if !can_adjoin(&adjunct, &host) {
+        // Report what blocks the adjunct: an unchecked trigger, else the
+        // `Adj` feature it offers instead
+        let found = adjunct.features.iter()
+            .find(|f| f.is_positive())
+            .or_else(|| adjunct.features.iter().find(|f| matches!(f.inner(), Feature::Adj(_))))
+            .cloned();
+        return Err(DerivationError::FeatureMismatch {
+            expected: Some(Feature::Adj(host.label.clone())),
+            found,
+        });
+    }
+    
+    let mut new_features = host.features.clone();
+    let mut adjunct_features = adjunct.features.clone();
+    remove_first(&mut adjunct_features, &Feature::Adj(host.label.clone()));
+    adjunct_features.retain(|f| !matches!(f, Feature::Cat(_)));
+    new_features.extend(adjunct_features);
+    
+    Ok(SyntacticObject::internal(
+        host.label.clone(),
+        new_features,
+        vec![adjunct, host],
+    ))
+}
+
+/// Check if `adjunct` can adjoin to `host`
+fn can_adjoin(adjunct: &SyntacticObject, host: &SyntacticObject) -> bool {
+    if adjunct.features.iter().any(|f| f.is_positive()) {
+        return false;
+    }
+    let hosts = |cat: &Category| host.features.iter().any(|f| matches!(f, Feature::Cat(c) if c == cat));
+    adjunct.features.iter().any(|f| matches!(f.inner(), Feature::Adj(cat) if hosts(cat)))
+}
+
+/// Merge, recording the two input feature bundles as the result's provenance
+///
+/// The bundles are stored in selector/selectee order, after any `Symmetric`
+/// role swap.
+pub fn merge_with_provenance(a: SyntacticObject, b: SyntacticObject) -> Result<SyntacticObject, DerivationError> {
+    let mut merged = merge(a, b)?;
+    let bundles = (merged.children[0].features.clone(), merged.children[1].features.clone());
+    merged.provenance = Some(Box::new(bundles));
+    Ok(merged)
+}
+
+/// Whether either object lets merge ignore argument order
+fn is_symmetric_pair(a: &SyntacticObject, b: &SyntacticObject) -> bool {
+    a.features.contains(&Feature::Symmetric) || b.features.contains(&Feature::Symmetric)
+}
+
+/// Merge with `a` as selector and `b` as selectee
+fn merge_directed(a: SyntacticObject, b: SyntacticObject) -> Result<SyntacticObject, DerivationError> {
+    let actual_cat = b.features.iter().find_map(|f| match f {
+        Feature::Cat(cat) => Some(cat),
+        _ => None,
+    });
+    
+    let satisfies = |cats: &[Category]| actual_cat.is_some_and(|cat| cats.contains(cat));
+    
+    // First selector of `a`, skipping optional selectors `b` cannot satisfy
+    let required = a.features.iter()
+        .find(|f| f.inner().is_selector() && (!f.is_optional() || satisfies(f.inner().selected())))
+        .map(Feature::inner);
+    let mismatch = |found: Option<&Feature>| DerivationError::FeatureMismatch {
+        expected: required.cloned(),
+        found: found.cloned(),
+    };
+    
+    // A selectee with a pending movement trigger must move before it projects
+    if let Some(trigger) = b.features.iter().find(|f| f.is_positive()) {
+        return Err(mismatch(Some(trigger)));
+    }
+    
     // Check if first object has selector feature matching second object's category
-    if let Some(sel_feature) = a.features.iter().find(|f| matches!(f, Feature::Sel(_))) {
-        if let Feature::Sel(required_cat) = sel_feature {
-            if let Some(cat_feature) = b.features.iter().find(|f| matches!(f, Feature::Cat(_))) {
-                if let Feature::Cat(actual_cat) = cat_feature {
-                    if required_cat == actual_cat {
-                        // Successful merge: create new object
-                        let mut new_features = a.features.clone();
-                        new_features.retain(|f| !matches!(f, Feature::Sel(_)));
-                        
-                        let mut b_features = b.features.clone();
-                        b_features.retain(|f| !matches!(f, Feature::Cat(_)));
-                        new_features.extend(b_features);
-                        
-                        return Ok(SyntacticObject::internal(
-                            a.label.clone(),
-                            new_features,
-                            vec![a, b],
-                        ));
+    match required {
+        Some(required) if satisfies(required.selected()) => {
+            if let (Some(x), Some(y)) = (a.agreement(), b.agreement()) {
+                if x != y {
+                    return Err(DerivationError::FeatureMismatch {
+                        expected: Some(Feature::Agr(x.to_string())),
+                        found: Some(Feature::Agr(y.to_string())),
+                    });
+                }
+            }
+            
+            // Successful merge: create new object
+            let mut new_features = a.features.clone();
+            new_features.retain(|f| !f.inner().is_selector());
+            
+            // The selectee's agreement is checked here; only the head's projects
+            let mut b_features = b.features.clone();
+            b_features.retain(|f| !matches!(f, Feature::Cat(_) | Feature::Symmetric | Feature::Agr(_)));
+            new_features.extend(b_features);
+            
+            let mut label = a.label.clone();
+            if let Some(phrase) = saturated_projection(&label) {
+                if !new_features.iter().any(Feature::is_selector) {
+                    for f in new_features.iter_mut().filter(|f| **f == Feature::Cat(label.clone())) {
+                        *f = Feature::Cat(phrase.clone());
                     }
+                    label = phrase;
                 }
             }
+            
+            Ok(SyntacticObject::internal(
+                label,
+                new_features,
+                vec![a, b],
+            ))
         }
+        _ => Err(mismatch(b.features.iter().find(|f| matches!(f, Feature::Cat(_))))),
+    }
+}
+
+/// Category a head projects once it has checked all its selectors
+///
+/// A preposition with its object is a PP, so grammars can select or adjoin
+/// to the whole phrase. Other heads keep their own category.
+fn saturated_projection(label: &Category) -> Option<Category> {
+    match label {
+        Category::P => Some(Category::PP),
+        _ => None,
+    }
+}
+
+/// Check that `a` and `b` do not both carry `Agr` with different values
+fn agrees(a: &SyntacticObject, b: &SyntacticObject) -> bool {
+    match (a.agreement(), b.agreement()) {
+        (Some(x), Some(y)) => x == y,
+        _ => true,
     }
-    
-    Err(DerivationError::FeatureMismatch)
 }
 
 /// Find pairs of objects that can merge
+///
+/// Pairs are ordered by the workspace's `MergePolicy`, or shuffled if it
+/// has a shuffle seed.
+///
+/// Rather than testing every ordered pair, items are indexed by the
+/// categories they offer (`Cat`) and the ones they look for (selectors and
+/// `Adj`), and each item is only tested against items on the other side of
+/// one of its categories. Merge and adjunction both need such a match, so
+/// no pair is missed.
 pub fn find_mergeable_pairs(workspace: &Workspace) -> Vec<(usize, usize)> {
-    let mut pairs = Vec::new();
-    
-    for i in 0..workspace.items.len() {
-        for j in 0..workspace.items.len() {
-            if i != j {
-                if can_merge(&workspace.items[i], &workspace.items[j]) {
-                    pairs.push((i, j));
+    let mut offers: BTreeMap<&Category, Vec<usize>> = BTreeMap::new();
+    let mut wants: BTreeMap<&Category, Vec<usize>> = BTreeMap::new();
+    for (i, item) in workspace.items.iter().enumerate() {
+        for feature in &item.features {
+            match feature.inner() {
+                Feature::Cat(cat) => offers.entry(cat).or_default().push(i),
+                Feature::Adj(cat) => wants.entry(cat).or_default().push(i),
+                selector => {
+                    for cat in selector.selected() {
+                        wants.entry(cat).or_default().push(i);
+                    }
                 }
             }
         }
     }
     
+    let mut pairs = Vec::new();
+    let mut candidates = Vec::new();
+    for (i, item) in workspace.items.iter().enumerate() {
+        candidates.clear();
+        for feature in &item.features {
+            let (cats, index) = match feature.inner() {
+                Feature::Cat(cat) => (core::slice::from_ref(cat), &wants),
+                Feature::Adj(cat) => (core::slice::from_ref(cat), &offers),
+                selector => (selector.selected(), &offers),
+            };
+            for cat in cats {
+                candidates.extend(index.get(cat).into_iter().flatten());
+            }
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+        
+        for &j in &candidates {
+            let adjacent = !workspace.require_adjacency || i.abs_diff(j) == 1;
+            if i != j
+                && adjacent
+                && can_merge(item, &workspace.items[j])
+                && workspace.arity_mismatch(item, &workspace.items[j]).is_none()
+            {
+                pairs.push((i, j));
+            }
+        }
+    }
+    
+    if workspace.merge_policy == MergePolicy::FewestFeatures {
+        // Stable, so equal counts keep their leftmost order
+        pairs.sort_by_key(|&(i, j)| merged_feature_count(&workspace.items[i], &workspace.items[j]));
+    }
+    
+    if let Some(seed) = workspace.shuffle_seed {
+        shuffle_seeded(&mut pairs, seed ^ workspace.step_count as u64);
+    }
+    
     pairs
 }
 
+/// Number of features `merge(a, b)` leaves on its result
+fn merged_feature_count(a: &SyntacticObject, b: &SyntacticObject) -> usize {
+    if !can_merge_directed(a, b) && !can_merge_directed(b, a) {
+        // Adjunction: the host's features plus the adjunct's leftovers
+        let (adjunct, host) = if can_adjoin(a, b) { (a, b) } else { (b, a) };
+        let leftover = adjunct.features.iter().filter(|f| !matches!(f, Feature::Cat(_))).count();
+        return host.features.len() + leftover.saturating_sub(1);
+    }
+    let (selector, selectee) = if can_merge_directed(a, b) { (a, b) } else { (b, a) };
+    selector.features.iter().filter(|f| !f.inner().is_selector()).count()
+        + selectee.features.iter().filter(|f| !matches!(f, Feature::Cat(_) | Feature::Symmetric)).count()
+}
+
+/// Fisher-Yates shuffle driven by a SplitMix64 generator
+fn shuffle_seeded<T>(items: &mut [T], seed: u64) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+    
+    for i in (1..items.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
 /// Check if two objects can merge
+///
+/// `b` must not carry an unchecked `Pos` feature: its movement has to be
+/// discharged before it can be selected, so each trigger gets its own
+/// landing site. Pairs involving a `Symmetric` item may merge either way,
+/// as may an adjunct and its host.
 pub fn can_merge(a: &SyntacticObject, b: &SyntacticObject) -> bool {
+    can_merge_directed(a, b)
+        || (is_symmetric_pair(a, b) && can_merge_directed(b, a))
+        || can_adjoin(a, b)
+        || can_adjoin(b, a)
+}
+
+/// Check if `a` can select `b`
+fn can_merge_directed(a: &SyntacticObject, b: &SyntacticObject) -> bool {
+    if b.features.iter().any(|f| f.is_positive()) || !agrees(a, b) {
+        return false;
+    }
+    
     // Check if a has selector feature matching b's category
     a.features.iter().any(|feat| {
-        if let Feature::Sel(required_cat) = feat {
+        feat.inner().selected().iter().any(|required_cat| {
             b.features.iter().any(|b_feat| {
                 matches!(b_feat, Feature::Cat(actual_cat) if actual_cat == required_cat)
             })
-        } else {
-            false
-        }
+        })
     })
 }
 
@@ -330,51 +1882,106 @@ pub fn can_merge(a: &SyntacticObject, b: &SyntacticObject) -> bool {
 // Core Operations: Move
 // ============================================================================
 
+/// Default bound on how many levels deep movement searches for its target
+pub const DEFAULT_MAX_MOVE_DEPTH: usize = 512;
+
 /// Apply movement operation to syntactic object
+///
+/// Triggers are tried in feature order; an optional trigger with no target
+/// is passed over so a later trigger can still fire. Each call resolves a
+/// single chain, so that one derivation step is one operation; an object
+/// with several triggers (`+1 +2`) has them resolved by successive steps,
+/// each against the mover carrying its own index.
 pub fn move_operation(obj: SyntacticObject) -> Result<SyntacticObject, DerivationError> {
+    move_operation_bounded(obj, DEFAULT_MAX_MOVE_DEPTH)
+}
+
+/// Apply movement, searching at most `max_depth` levels for the target
+///
+/// A target buried deeper than that yields `MemoryLimitExceeded` instead of
+/// recursing further.
+pub fn move_operation_bounded(obj: SyntacticObject, max_depth: usize) -> Result<SyntacticObject, DerivationError> {
     // Find positive feature that triggers movement
-    if let Some(pos_feature) = obj.features.iter().find(|f| f.is_positive()) {
-        if let Some(movement_idx) = pos_feature.movement_index() {
+    let triggers = obj.features.iter().filter(|f| f.inner().is_positive());
+    for pos_feature in triggers {
+        if let Some(movement_idx) = pos_feature.inner().movement_index() {
             // Search for matching negative feature in embedded structure
-            if let Some(target) = find_movement_target(&obj, movement_idx) {
-                return extract_and_move(obj, target, movement_idx);
+            if let Some(path) = find_movement_target(&obj, movement_idx, max_depth)? {
+                return extract_and_move(obj, &path, movement_idx);
             }
         }
+        if !pos_feature.is_optional() {
+            break;
+        }
     }
     
     Err(DerivationError::NoValidOperations)
 }
 
 /// Find constituent with matching negative feature
-fn find_movement_target(obj: &SyntacticObject, movement_idx: u8) -> Option<SyntacticObject> {
-    // Check current object
-    if obj.features.iter().any(|f| matches!(f, Feature::Neg(idx) if *idx == movement_idx)) {
-        return Some(obj.clone());
+///
+/// Unchecked features percolate up from selectees, so every node on the path
+/// from `obj` down to the `Neg` bearer carries the feature. The mover is the
+/// lowest non-head node on that path: the maximal projection of the bearer,
+/// or its most recent landing site if it has already moved. Returns the
+/// child indices leading from `obj` to the mover.
+fn find_movement_target(
+    obj: &SyntacticObject,
+    movement_idx: u8,
+    depth_left: usize,
+) -> Result<Option<Vec<usize>>, DerivationError> {
+    if depth_left == 0 {
+        return Err(DerivationError::MemoryLimitExceeded);
     }
     
-    // Recursively search children
-    for child in &obj.children {
-        if let Some(target) = find_movement_target(child, movement_idx) {
-            return Some(target);
+    let carries = |o: &SyntacticObject| {
+        o.features.iter().any(|f| matches!(f.inner(), Feature::Neg(idx) if *idx == movement_idx))
+    };
+    let head = obj.head_index();
+    
+    // Prefer non-head children: a landing site sits above its original copy
+    let mut order: Vec<usize> = (0..obj.children.len()).filter(|&k| Some(k) != head).collect();
+    order.extend(head);
+    
+    for k in order {
+        let child = &obj.children[k];
+        if carries(child) {
+            return Ok(match find_movement_target(child, movement_idx, depth_left - 1)? {
+                Some(mut path) => {
+                    path.insert(0, k);
+                    Some(path)
+                }
+                None if Some(k) != head => Some(vec![k]),
+                None => None,
+            });
         }
     }
     
-    None
+    Ok(None)
 }
 
-/// Extract target and adjoin to edge position
+/// Extract the target at `path`, leaving a trace, and adjoin it at the edge
 fn extract_and_move(
-    obj: SyntacticObject, 
-    target: SyntacticObject, 
+    mut obj: SyntacticObject, 
+    path: &[usize], 
     movement_idx: u8
 ) -> Result<SyntacticObject, DerivationError> {
-    // Remove positive feature from trigger
+    let mut site = &mut obj;
+    for &k in path {
+        site = &mut site.children[k];
+    }
+    let trace = SyntacticObject::trace(site.label.clone(), movement_idx);
+    let target = core::mem::replace(site, trace);
+    
+    // Check the trigger against the percolated copy of the target's feature
     let mut new_features = obj.features.clone();
-    new_features.retain(|f| !matches!(f, Feature::Pos(idx) if *idx == movement_idx));
+    remove_first(&mut new_features, &Feature::Pos(movement_idx));
+    remove_first(&mut new_features, &Feature::Neg(movement_idx));
     
-    // Remove negative feature from target
+    // Remove negative feature from target, keeping any further movement
+    // features so it can move again to their landing sites
     let mut target_features = target.features.clone();
-    target_features.retain(|f| !matches!(f, Feature::Neg(idx) if *idx == movement_idx));
+    remove_first(&mut target_features, &Feature::Neg(movement_idx));
     
     let moved_target = SyntacticObject {
         features: target_features,
@@ -389,12 +1996,106 @@ fn extract_and_move(
     ))
 }
 
+/// Remove the first occurrence of a feature, optional or not, from a bundle
+fn remove_first(features: &mut Vec<Feature>, feature: &Feature) {
+    if let Some(pos) = features.iter().position(|f| f.inner() == feature) {
+        features.remove(pos);
+    }
+}
+
 // ============================================================================
 // Derivation Engine
 // ============================================================================
 
+/// Operation performed by one derivation step
+///
+/// Indices refer to workspace item positions just before the step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepKind {
+    /// Merge of two workspace items; the result is pushed to the end
+    Merge {
+        /// Index of the selecting item
+        selector: usize,
+        /// Index of the selected item
+        selectee: usize,
+    },
+    /// Movement inside one workspace item, which stays in place
+    Move {
+        /// Index of the item
+        item: usize,
+    },
+}
+
+/// One record of a derivation log, as returned by `derive_traced`
+///
+/// An operation `Merge { left, right }` is `StepKind::Merge { selector,
+/// selectee }`, with `left` the index of the selecting item and `right` that
+/// of the selected one. `Move { index }` is `StepKind::Move { item }`.
+pub type Operation = StepKind;
+
+/// Replace a selector/selectee pair with the result of merging them: in
+/// the pair's place under `require_adjacency`, else at the end
+fn replace_pair(workspace: &mut Workspace, selector: usize, selectee: usize, merged: SyntacticObject) {
+    // Remove the higher index first so the lower one stays valid
+    workspace.items.remove(selector.max(selectee));
+    workspace.items.remove(selector.min(selectee));
+    if workspace.require_adjacency {
+        workspace.items.insert(selector.min(selectee), merged);
+    } else {
+        workspace.items.push(merged);
+    }
+}
+
 /// Single derivation step
 pub fn step(workspace: &mut Workspace) -> Result<(), DerivationError> {
+    traced_step(workspace).map(|_| ())
+}
+
+/// Operations to try on `workspace`, in the order `step` tries them:
+/// every pair `find_mergeable_pairs` offers, then movement in each item
+/// unless the workspace is merge-only
+fn candidate_operations(workspace: &Workspace) -> Vec<StepKind> {
+    let merges = find_mergeable_pairs(workspace)
+        .into_iter()
+        .map(|(selector, selectee)| StepKind::Merge { selector, selectee });
+    let movable = if workspace.merge_only { 0 } else { workspace.items.len() };
+    merges.chain((0..movable).map(|item| StepKind::Move { item })).collect()
+}
+
+/// Perform one operation on the workspace, counting the attempt
+fn apply_operation(workspace: &mut Workspace, operation: &StepKind) -> Result<(), DerivationError> {
+    match *operation {
+        StepKind::Merge { selector, selectee } => {
+            workspace.merge_attempts += 1;
+            // Merge copies, so a rejected merge leaves the pair in place
+            let (a, b) = (workspace.items[selector].clone(), workspace.items[selectee].clone());
+            let merged = if workspace.track_provenance {
+                merge_with_provenance(a, b)
+            } else {
+                merge(a, b)
+            }?;
+            if let Some(cat) = workspace.exceeded_embedding_limit(&merged) {
+                return Err(DerivationError::EmbeddingLimitExceeded(cat));
+            }
+            workspace.merge_successes += 1;
+            replace_pair(workspace, selector, selectee, merged);
+            Ok(())
+        }
+        StepKind::Move { item } => {
+            workspace.move_attempts += 1;
+            let moved = move_operation_bounded(workspace.items[item].clone(), workspace.max_move_depth)?;
+            workspace.move_successes += 1;
+            workspace.items[item] = moved;
+            Ok(())
+        }
+    }
+}
+
+/// Single derivation step, reporting which operation it performed
+///
+/// The first candidate merge is committed to, failing the step if it fails;
+/// movement is tried item by item until one moves.
+fn traced_step(workspace: &mut Workspace) -> Result<StepKind, DerivationError> {
     if workspace.items.is_empty() {
         return Err(DerivationError::EmptyWorkspace);
     }
@@ -406,314 +2107,5126 @@ pub fn step(workspace: &mut Workspace) -> Result<(), DerivationError> {
         return Err(DerivationError::MemoryLimitExceeded);
     }
     
-    // Try merge operations first
-    let mergeable_pairs = find_mergeable_pairs(workspace);
-    if let Some((i, j)) = mergeable_pairs.first() {
-        let a = workspace.items.remove(*i.max(j));
-        let b = workspace.items.remove(*i.min(j));
-        
-        match merge(a, b) {
-            Ok(merged) => {
-                workspace.items.push(merged);
-                return Ok(());
-            }
-            Err(e) => return Err(e),
-        }
-    }
-    
-    // Try move operations
-    for i in 0..workspace.items.len() {
-        if let Ok(moved) = move_operation(workspace.items[i].clone()) {
-            workspace.items[i] = moved;
-            return Ok(());
+    for operation in candidate_operations(workspace) {
+        match apply_operation(workspace, &operation) {
+            Ok(()) => return Ok(operation),
+            Err(e) if matches!(operation, StepKind::Merge { .. }) => return Err(e),
+            Err(DerivationError::MemoryLimitExceeded) => return Err(DerivationError::MemoryLimitExceeded),
+            Err(_) => {}
         }
     }
     
-    Err(DerivationError::NoValidOperations)
+    Err(workspace.stuck_error())
 }
 
 /// Run complete derivation
 pub fn derive(workspace: &mut Workspace, max_steps: usize) -> Result<SyntacticObject, DerivationError> {
+    derive_traced(workspace, max_steps).map(|(tree, _)| tree)
+}
+
+/// Run complete derivation, logging the operation of every step
+///
+/// A derivation that ends in a single saturated object whose label is not
+/// the workspace's start category fails with `WrongStartCategory`.
+/// Replaying the log with `apply_script` on a copy of the workspace as it
+/// was before the call reproduces the tree. `parse_traced` does the same for
+/// a sentence in a default workspace.
+pub fn derive_traced(workspace: &mut Workspace, max_steps: usize) -> Result<(SyntacticObject, Vec<Operation>), DerivationError> {
+    let mut log = Vec::new();
+    let result = |workspace: &Workspace| {
+        let tree = workspace.items[0].clone();
+        debug_assert_eq!(tree.validate(), Ok(()), "derivation built a malformed tree");
+        // Merges already kept to the arity table; this catches heads that
+        // never selected at all
+        if !workspace.arity.is_empty() {
+            tree.check_arity(&workspace.arity)?;
+        }
+        Ok(tree)
+    };
+    
     for _ in 0..max_steps {
         if workspace.is_successful() {
-            return Ok(workspace.items[0].clone());
+            return Ok((result(workspace)?, log));
         }
         
-        if let Err(e) = step(workspace) {
-            if e == DerivationError::NoValidOperations {
-                break; // Derivation stuck
-            }
-            return Err(e);
+        match traced_step(workspace) {
+            Ok(kind) => log.push(kind),
+            Err(DerivationError::NoValidOperations) => break, // Derivation stuck
+            Err(e) => return Err(e),
         }
     }
     
     if workspace.is_successful() {
-        Ok(workspace.items[0].clone())
-    } else {
-        Err(DerivationError::NoValidOperations)
+        return Ok((result(workspace)?, log));
+    }
+    match (&workspace.start_category, workspace.items.as_slice()) {
+        (Some(cat), [tree]) if tree.is_saturated() => Err(DerivationError::WrongStartCategory {
+            expected: cat.clone(),
+            found: tree.label.clone(),
+        }),
+        _ => Err(DerivationError::NoValidOperations),
     }
 }
 
-// ============================================================================
-// Lexicon and Grammar
-// ============================================================================
+/// Iterator over the workspace states of a derivation
+///
+/// Each item is a snapshot of the workspace after one `step`. Iteration ends
+/// once the workspace is successful or no operation applies; any other
+/// error is yielded once, after which the iterator is exhausted.
+#[derive(Debug, Clone)]
+pub struct DerivationIterator {
+    workspace: Workspace,
+    done: bool,
+}
 
-/// Standard test lexicon for recursive patterns
-pub fn test_lexicon() -> Vec<LexItem> {
-    vec![
-        LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
-        LexItem::new("a", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
-        LexItem::new("student", &[Feature::Cat(Category::N)]),
-        LexItem::new("tutor", &[Feature::Cat(Category::N)]),
-        LexItem::new("teacher", &[Feature::Cat(Category::N)]),
-        LexItem::new("who", &[Feature::Cat(Category::C), Feature::Sel(Category::S)]),
-        LexItem::new("that", &[Feature::Cat(Category::C), Feature::Sel(Category::S)]),
-        LexItem::new("said", &[Feature::Cat(Category::V), Feature::Sel(Category::DP), Feature::Pos(1)]),
-        LexItem::new("thinks", &[Feature::Cat(Category::V), Feature::Sel(Category::DP)]),
-        LexItem::new("left", &[Feature::Cat(Category::V)]),
-        LexItem::new("smiled", &[Feature::Cat(Category::V)]),
-        LexItem::new("arrived", &[Feature::Cat(Category::V)]),
-    ]
+impl DerivationIterator {
+    /// Step through the derivation of `workspace`
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace, done: false }
+    }
 }
 
-/// Generate aⁿbⁿ pattern for testing recursion
-pub fn generate_an_bn(n: usize) -> String {
-    if n == 0 {
-        String::new()
-    } else {
-        let a_s = std::iter::repeat("a").take(n).collect::<Vec<_>>().join(" ");
-        let b_s = std::iter::repeat("b").take(n).collect::<Vec<_>>().join(" ");
-        format!("{} {}", a_s, b_s)
+impl Iterator for DerivationIterator {
+    type Item = Result<Workspace, DerivationError>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.workspace.is_successful() {
+            return None;
+        }
+        
+        match step(&mut self.workspace) {
+            Ok(()) => Some(Ok(self.workspace.clone())),
+            Err(DerivationError::NoValidOperations) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
     }
 }
 
-/// Test if string matches aⁿbⁿ pattern
-pub fn is_an_bn_pattern(s: &str) -> bool {
-    let tokens: Vec<&str> = s.split_whitespace().collect();
-    if tokens.is_empty() {
-        return true; // ε case
+/// Describe, item by item, why a workspace cannot finish its derivation
+///
+/// Each line names the lexical item behind an unchecked requirement: a
+/// selector with nothing to select, a `Neg` mover with no matching `Pos`
+/// trigger (or the reverse), or an adjunct with no host. Selectors look at
+/// the next workspace item, which is the following token until merges
+/// reorder the workspace. Complete items that nothing selected are reported
+/// as unattached. Returns an empty string for a successful workspace.
+pub fn explain_failure(workspace: &Workspace) -> String {
+    fn name(leaf: &SyntacticObject) -> String {
+        match leaf.phon.as_deref() {
+            Some(phon) if !phon.is_empty() => phon.to_string(),
+            _ => format!("silent {:?}", leaf.label),
+        }
     }
     
-    let n = tokens.len() / 2;
-    if tokens.len() != 2 * n {
-        return false;
+    fn carrier<'a>(node: &'a SyntacticObject, feature: &Feature) -> Option<&'a SyntacticObject> {
+        if node.children.is_empty() {
+            return (!node.is_trace() && node.features.contains(feature)).then_some(node);
+        }
+        node.children.iter().find_map(|child| carrier(child, feature))
     }
     
-    // Check first n tokens are 'a'
-    for i in 0..n {
-        if tokens[i] != "a" {
-            return false;
-        }
+    if workspace.is_successful() {
+        return String::new();
+    }
+    if workspace.items.is_empty() {
+        return "the workspace is empty".to_string();
     }
     
-    // Check last n tokens are 'b'
-    for i in n..2*n {
-        if tokens[i] != "b" {
-            return false;
+    let mut lines = Vec::new();
+    for (i, item) in workspace.items.iter().enumerate() {
+        let next = workspace.items.get(i + 1);
+        for feature in &item.features {
+            // Open selectors always belong to the rightmost head; other
+            // features to the leaf that brought them in
+            let owner = if feature.is_selector() {
+                item.selector_owner()
+            } else {
+                carrier(item, feature).unwrap_or(item)
+            };
+            let line = match feature {
+                Feature::Sel(_) | Feature::SelAny(_) => {
+                    let wanted = feature.selected().iter()
+                        .map(|cat| format!("{:?}", cat))
+                        .collect::<Vec<_>>()
+                        .join(" or ");
+                    let found = match next {
+                        Some(next) => format!("found {} ({:?})", next.linearize(), next.label),
+                        None => "the sequence ended".to_string(),
+                    };
+                    format!("{} expected a following {} but {}", name(owner), wanted, found)
+                }
+                Feature::Neg(n) => format!("{} carries Neg({}) but no Pos({}) triggers its movement", name(owner), n, n),
+                Feature::Pos(n) => format!("{} carries Pos({}) but no Neg({}) mover is in its phrase", name(owner), n, n),
+                Feature::Adj(cat) => format!("{} found no {:?} to adjoin to", name(owner), cat),
+                _ => continue,
+            };
+            lines.push(line);
+        }
+        
+        if item.is_saturated() && workspace.items.len() > 1 {
+            lines.push(format!("{} ({:?}) was left unattached", item.linearize(), item.label));
         }
     }
     
-    true
+    if lines.is_empty() {
+        if let Some(cat) = &workspace.start_category {
+            lines.push(format!("the derivation built a {:?} but a {:?} was required", workspace.items[0].label, cat));
+        }
+    }
+    lines.join("\n")
 }
 
-// ============================================================================
-// Public API
-// ============================================================================
-
-/// Parse sentence using Minimalist Grammar
-pub fn parse_sentence(sentence: &str, lexicon: &[LexItem]) -> Result<SyntacticObject, DerivationError> {
-    let tokens: Vec<&str> = sentence.split_whitespace().collect();
-    let mut workspace = Workspace::new(4096); // 4KB memory limit
+/// Replay a logged sequence of operations on a workspace
+///
+/// Each operation is applied exactly as recorded, without consulting
+/// `find_mergeable_pairs`, so replaying the log of a derivation on a fresh
+/// copy of its initial workspace rebuilds the same tree. Out-of-range
+/// indices yield `InvalidOperation`. A step that fails stops the replay and
+/// leaves the items as the steps before it left them.
+pub fn apply_script(workspace: &mut Workspace, script: &[StepKind]) -> Result<(), DerivationError> {
+    for kind in script {
+        workspace.step_count += 1;
+        match *kind {
+            StepKind::Merge { selector, selectee } => {
+                let len = workspace.items.len();
+                if selector == selectee || selector >= len || selectee >= len {
+                    return Err(DerivationError::InvalidOperation);
+                }
+                let merged = merge(workspace.items[selector].clone(), workspace.items[selectee].clone())?;
+                replace_pair(workspace, selector, selectee, merged);
+            }
+            StepKind::Move { item } => {
+                let obj = workspace.items.get(item).ok_or(DerivationError::InvalidOperation)?;
+                workspace.items[item] = move_operation_bounded(obj.clone(), workspace.max_move_depth)?;
+            }
+        }
+    }
     
-    // Add tokens to workspace
-    for token in tokens {
-        if let Some(lex_item) = lexicon.iter().find(|item| item.phon == token) {
-            workspace.add_lex(lex_item);
+    Ok(())
+}
+
+/// Every workspace reachable from `workspace` by a single merge or move
+fn successors(workspace: &Workspace) -> Vec<Workspace> {
+    candidate_operations(workspace)
+        .iter()
+        .filter_map(|operation| {
+            let mut ws = workspace.clone();
+            ws.step_count += 1;
+            apply_operation(&mut ws, operation).ok().map(|()| ws)
+        })
+        .collect()
+}
+
+/// Workspace items in a fixed order, so workspaces holding the same
+/// multiset of items share one key
+fn canonical_items(workspace: &Workspace) -> Vec<SyntacticObject> {
+    let mut items = workspace.items.clone();
+    items.sort_unstable();
+    items
+}
+
+/// Check whether any sequence of at most `budget` operations reaches success
+///
+/// `dead_ends` maps the canonical items of each workspace already found
+/// unrecoverable to the largest budget it failed with; a workspace reached
+/// again with no more budget than that is not searched twice.
+fn is_recoverable(workspace: &Workspace, budget: usize, dead_ends: &mut BTreeMap<Vec<SyntacticObject>, usize>) -> bool {
+    if workspace.is_successful() {
+        return true;
+    }
+    if budget == 0 || workspace.memory_usage() > workspace.memory_limit {
+        return false;
+    }
+    
+    let key = canonical_items(workspace);
+    if dead_ends.get(&key).is_some_and(|&failed| failed >= budget) {
+        return false;
+    }
+    let recoverable = successors(workspace).iter().any(|ws| is_recoverable(ws, budget - 1, dead_ends));
+    if !recoverable {
+        dead_ends.insert(key, budget);
+    }
+    recoverable
+}
+
+/// Collect every distinct tree derivable from `workspace` within `budget`
+/// steps, stopping once `out` holds `max_trees`
+fn all_derivations(workspace: &Workspace, budget: usize, max_trees: usize, out: &mut Vec<SyntacticObject>) {
+    if out.len() >= max_trees {
+        return;
+    }
+    if workspace.is_successful() {
+        let tree = &workspace.items[0];
+        if !out.iter().any(|seen| seen.structurally_equivalent(tree)) {
+            out.push(tree.clone());
+        }
+        return;
+    }
+    if budget == 0 || workspace.memory_usage() > workspace.memory_limit {
+        return;
+    }
+    
+    for ws in successors(workspace) {
+        all_derivations(&ws, budget - 1, max_trees, out);
+    }
+}
+
+/// Add the `structure_key` of every tree derivable from `workspace` within
+/// `budget` steps, without keeping the trees themselves
+fn derivation_keys(workspace: &Workspace, budget: usize, keys: &mut BTreeSet<String>) {
+    if workspace.is_successful() {
+        keys.insert(workspace.items[0].structure_key());
+        return;
+    }
+    if budget == 0 || workspace.memory_usage() > workspace.memory_limit {
+        return;
+    }
+    
+    for ws in successors(workspace) {
+        derivation_keys(&ws, budget - 1, keys);
+    }
+}
+
+/// `all_derivations` with the first step's branches explored on the rayon
+/// thread pool
+///
+/// Each branch is searched on its own, capped at `max_trees`, and the
+/// results are merged into `out` in branch order with the same
+/// deduplication, so the trees and their order match the serial search
+/// exactly.
+#[cfg(feature = "rayon")]
+fn all_derivations_parallel(workspace: &Workspace, budget: usize, max_trees: usize, out: &mut Vec<SyntacticObject>) {
+    use rayon::prelude::*;
+    
+    if workspace.is_successful() || budget == 0 || workspace.memory_usage() > workspace.memory_limit {
+        all_derivations(workspace, budget, max_trees, out);
+        return;
+    }
+    
+    let branches: Vec<Vec<SyntacticObject>> = successors(workspace)
+        .par_iter()
+        .map(|ws| {
+            let mut trees = Vec::new();
+            all_derivations(ws, budget - 1, max_trees, &mut trees);
+            trees
+        })
+        .collect();
+    
+    for tree in branches.into_iter().flatten() {
+        if out.len() >= max_trees {
+            break;
+        }
+        if !out.iter().any(|seen: &SyntacticObject| seen.structurally_equivalent(&tree)) {
+            out.push(tree);
+        }
+    }
+}
+
+/// Find the step after which the greedy derivation can no longer succeed
+///
+/// Replays `derive` from `workspace_initial` and, after each step, searches
+/// exhaustively for any continuation reaching success within the remaining
+/// budget. Returns the number of steps taken when the derivation first became
+/// unrecoverable (`Some(0)` if it never had a chance), or `None` if the
+/// derivation succeeds.
+///
+/// Dead ends found by one search are remembered for the later ones, so each
+/// distinct workspace is expanded at most once per budget.
+pub fn find_failure_step(workspace_initial: &Workspace, max_steps: usize) -> Option<usize> {
+    let mut workspace = workspace_initial.clone();
+    let mut dead_ends = BTreeMap::new();
+    
+    for taken in 0..=max_steps {
+        if workspace.is_successful() {
+            return None;
+        }
+        if !is_recoverable(&workspace, max_steps - taken, &mut dead_ends) {
+            return Some(taken);
+        }
+        if step(&mut workspace).is_err() {
+            return Some(taken);
+        }
+    }
+    
+    None
+}
+
+// ============================================================================
+// Lexicon and Grammar
+// ============================================================================
+
+/// Standard test lexicon for recursive patterns
+pub fn test_lexicon() -> Vec<LexItem> {
+    vec![
+        LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+        LexItem::new("a", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+        LexItem::new("student", &[Feature::Cat(Category::N)]),
+        LexItem::new("tutor", &[Feature::Cat(Category::N)]),
+        LexItem::new("teacher", &[Feature::Cat(Category::N)]),
+        LexItem::new("who", &[Feature::Cat(Category::C), Feature::Sel(Category::S)]),
+        LexItem::new("that", &[Feature::Cat(Category::C), Feature::Sel(Category::S)]),
+        LexItem::new("said", &[Feature::Cat(Category::V), Feature::Sel(Category::DP), Feature::Pos(1)]),
+        LexItem::new("thinks", &[Feature::Cat(Category::V), Feature::Sel(Category::DP)]),
+        LexItem::new("left", &[Feature::Cat(Category::V)]),
+        LexItem::new("smiled", &[Feature::Cat(Category::V)]),
+        LexItem::new("arrived", &[Feature::Cat(Category::V)]),
+    ]
+}
+
+/// The test lexicon with verbal modifiers: the adverb "quickly" and the
+/// prepositions "in" and "on", which select a D, project a PP once they have
+/// it, and adjoin to a V
+pub fn modifier_lexicon() -> Vec<LexItem> {
+    let mut lexicon = test_lexicon();
+    lexicon.extend([
+        LexItem::new("quickly", &[Feature::Cat(Category::Adv), Feature::Adj(Category::V)]),
+        LexItem::new("in", &[Feature::Cat(Category::P), Feature::Sel(Category::D), Feature::Adj(Category::V)]),
+        LexItem::new("on", &[Feature::Cat(Category::P), Feature::Sel(Category::D), Feature::Adj(Category::V)]),
+    ]);
+    lexicon
+}
+
+/// The grammar of space operations used to validate mission logs
+pub fn space_operations_lexicon() -> Vec<LexItem> {
+    LexiconBuilder::new()
+        // CONTEXTS: Mission modes. A log opens with a context that selects a command.
+        .words(&["CTX_DRIVE", "CTX_SCIENCE", "CTX_STANDBY"])
+        .cat(Category::Context).sel(Category::Command).done()
+
+        // COMMANDS: Actions that can be taken. A command selects a state.
+        .words(&["MOTOR_CMD_START", "MOTOR_CMD_STOP", "INSTRUMENT_PWR_ON", "INSTRUMENT_PWR_OFF"])
+        .cat(Category::Command).sel(Category::State).done()
+
+        // STATES: Observations about the system. 
+        // A state can select another state, allowing for a valid chain of telemetry.
+        .word("VOLTAGE_SPIKE").cat(Category::State).done() // Terminal state, cannot select another.
+        .words(&["CURRENT_DRAW", "WHEEL_RPM", "TEMP_MOTOR", "TEMP_INSTRUMENT", "SPECTROMETER_READ"])
+        .cat(Category::State).sel(Category::State).done()
+        .build()
+}
+
+/// A small grammar of wh-questions built by movement
+///
+/// Wh-words are DPs carrying `Neg(1)`, verbs select their DP argument, and
+/// a silent complementizer (empty phon) selects the VP and triggers `Pos(1)`,
+/// so the wh-word moves to the edge of the CP. The silent C is not typed in
+/// the sentence; `parse_sentence` loads it alongside the words.
+pub fn wh_movement_lexicon() -> Vec<LexItem> {
+    vec![
+        LexItem::new("who", &[Feature::Cat(Category::D), Feature::Neg(1)]),
+        LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1)]),
+        LexItem::new("left", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+        LexItem::new("arrived", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+        LexItem::covert(&[Feature::Cat(Category::C), Feature::Sel(Category::V), Feature::Pos(1)]),
+    ]
+}
+
+/// Malformed line in a textual lexicon
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexiconParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column of the offending text
+    pub column: usize,
+    /// What was wrong with it
+    pub message: String,
+}
+
+impl fmt::Display for LexiconParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Unrecognized category name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCategoryError {
+    /// The name that failed to parse
+    pub name: String,
+}
+
+impl fmt::Display for ParseCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown category `{}`", self.name)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseCategoryError {}
+
+/// Feature written in the lexicon notation that failed to parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseFeatureError {
+    /// Category name not recognized
+    UnknownCategory(ParseCategoryError),
+    /// Movement index not a number in `0..=255`
+    InvalidIndex(String),
+}
+
+impl fmt::Display for ParseFeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseFeatureError::UnknownCategory(err) => write!(f, "{}", err),
+            ParseFeatureError::InvalidIndex(idx) => write!(f, "invalid movement index `{}`", idx),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseFeatureError {}
+
+/// Categories parse from their variant names, e.g. `"D"` or `"Command"`
+impl FromStr for Category {
+    type Err = ParseCategoryError;
+    
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "N" => Category::N,
+            "V" => Category::V,
+            "D" => Category::D,
+            "C" => Category::C,
+            "S" => Category::S,
+            "NP" => Category::NP,
+            "VP" => Category::VP,
+            "DP" => Category::DP,
+            "CP" => Category::CP,
+            "Adv" => Category::Adv,
+            "P" => Category::P,
+            "PP" => Category::PP,
+            "Event" => Category::Event,
+            "Command" => Category::Command,
+            "State" => Category::State,
+            "Context" => Category::Context,
+            _ => return Err(ParseCategoryError { name: name.to_string() }),
+        })
+    }
+}
+
+/// Features parse from the notation used by `parse_lexicon`: a bare
+/// category is `Cat`, `=X` is `Sel(X)`, `=X|Y` is `SelAny([X, Y])`, and
+/// `+k`/`-k` are `Pos(k)`/`Neg(k)`
+impl FromStr for Feature {
+    type Err = ParseFeatureError;
+    
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        let index = |idx: &str| idx.parse().map_err(|_| ParseFeatureError::InvalidIndex(idx.to_string()));
+        
+        if let Some(names) = token.strip_prefix('=') {
+            if names.contains('|') {
+                names.split('|').map(str::parse).collect::<Result<_, _>>()
+                    .map(Feature::SelAny).map_err(ParseFeatureError::UnknownCategory)
+            } else {
+                names.parse().map(Feature::Sel).map_err(ParseFeatureError::UnknownCategory)
+            }
+        } else if let Some(idx) = token.strip_prefix('+') {
+            index(idx).map(Feature::Pos)
+        } else if let Some(idx) = token.strip_prefix('-') {
+            index(idx).map(Feature::Neg)
         } else {
-            return Err(DerivationError::UnknownToken(token.to_string()));
+            token.parse().map(Feature::Cat).map_err(ParseFeatureError::UnknownCategory)
+        }
+    }
+}
+
+/// Parse a lexicon in the line-oriented MG notation
+///
+/// Each line reads `phon :: features`, e.g. `the :: D =N`, with each
+/// feature written as `Feature::from_str` expects; a covert item is written
+/// with phon `ε`. A phon may span several words (`kick the bucket :: V`),
+/// stored with single spaces between them. Blank lines and lines starting
+/// with `#` are skipped.
+pub fn parse_lexicon(src: &str) -> Result<Vec<LexItem>, LexiconParseError> {
+    let mut lexicon = Vec::new();
+    
+    for (line_idx, line) in src.lines().enumerate() {
+        let error = |byte_offset: usize, message: String| LexiconParseError {
+            line: line_idx + 1,
+            column: line[..byte_offset].chars().count() + 1,
+            message,
+        };
+        
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        
+        let sep = line.find("::")
+            .ok_or_else(|| error(line.len() - trimmed.len(), "expected `::` between phon and features".to_string()))?;
+        let phon = match line[..sep].trim() {
+            "" => return Err(error(sep, "missing phon before `::`".to_string())),
+            "ε" => "",
+            phon => phon,
+        };
+        
+        let mut feats = Vec::new();
+        let mut offset = sep + 2;
+        for token in line[sep + 2..].split_whitespace() {
+            let start = offset + line[offset..].find(token).unwrap_or(0);
+            offset = start + token.len();
+            
+            let feature = token.parse::<Feature>().map_err(|err| {
+                // Point past the `=`/`+`/`-` prefix at the part that failed
+                let prefix = usize::from(token.starts_with(['=', '+', '-']));
+                error(start + prefix, err.to_string())
+            })?;
+            feats.push(feature);
+        }
+        
+        let phon = phon.split_whitespace().collect::<Vec<_>>().join(" ");
+        lexicon.push(LexItem::new(&phon, &feats));
+    }
+    
+    Ok(lexicon)
+}
+
+/// A lexicon together with the category its sentences must derive
+///
+/// With the `serde` feature a grammar reads from and writes to JSON:
+///
+/// ```json
+/// {
+///   "lexicon": [
+///     { "phon": "the", "features": [{ "Cat": "D" }, { "Sel": "N" }] },
+///     { "phon": "student", "features": [{ "Cat": "N" }], "weight": 0.5 }
+///   ],
+///   "start_category": "D"
+/// }
+/// ```
+///
+/// `weight` may be omitted and defaults to 1.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grammar {
+    /// Lexical items
+    pub lexicon: Vec<LexItem>,
+    /// Category a complete sentence must have
+    pub start_category: Category,
+}
+
+impl Grammar {
+    /// Bundle a lexicon with its start category
+    pub fn new(lexicon: Vec<LexItem>, start_category: Category) -> Self {
+        Self { lexicon, start_category }
+    }
+    
+    /// Parse a sentence, requiring the result to have the start category
+    ///
+    /// The start category is set on the workspace, so the check is the one
+    /// `derive` makes.
+    pub fn parse(&self, sentence: &str) -> Result<SyntacticObject, DerivationError> {
+        let tokens: Vec<&str> = sentence.split_whitespace().collect();
+        check_token_count(&tokens, DEFAULT_MAX_TOKENS)?;
+        let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT).with_start_category(self.start_category.clone());
+        load_tokens(&mut workspace, &tokens, &self.lexicon)
+            .and_then(|()| derive(&mut workspace, DEFAULT_MAX_STEPS))
+            .map_err(|e| e.located_in(sentence, &tokens))
+    }
+    
+    /// Find the selection cycles that let a category select itself
+    ///
+    /// One warning is reported per elementary cycle through required
+    /// selectors, whether or not it is reachable from the start category.
+    /// See `RecursionKind` for how productive recursion is told apart from
+    /// a loop no derivation can leave.
+    pub fn check_termination(&self) -> Vec<TerminationWarning> {
+        selection_cycles(&self.lexicon)
+    }
+    
+    /// Find movement indices used with only one polarity
+    ///
+    /// A `Pos(i)` trigger with no `Neg(i)` anywhere in the lexicon can never
+    /// fire, and a `Neg(i)` mover with no `Pos(i)` can never be checked. One
+    /// warning is reported per unpaired feature, listing every item that
+    /// carries it. Unlike `check_movement_reachability`, this ignores where
+    /// the features could occur in a tree.
+    pub fn check_movement_features(&self) -> Vec<FeatureWarning> {
+        unpaired_movement_features(&self.lexicon)
+    }
+    
+    /// Read a grammar from its JSON form
+    #[cfg(feature = "serde")]
+    pub fn from_json(src: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(src)
+    }
+    
+    /// Write the grammar as pretty-printed JSON
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Fluent construction of a lexicon
+///
+/// ```
+/// # use atomic_lang_model_python::*;
+/// let lexicon = LexiconBuilder::new()
+///     .word("the").cat(Category::D).sel(Category::N).done()
+///     .words(&["student", "tutor"]).cat(Category::N).done()
+///     .build();
+/// assert_eq!(lexicon[0], LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]));
+/// assert_eq!(lexicon.len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LexiconBuilder {
+    items: Vec<LexItem>,
+}
+
+impl LexiconBuilder {
+    /// Start an empty lexicon
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Begin an entry for `phon`; its features follow, ended by `done`
+    pub fn word(self, phon: &str) -> LexEntryBuilder {
+        self.words(&[phon])
+    }
+    
+    /// Begin entries sharing one feature bundle, one item per phon
+    pub fn words(self, phons: &[&str]) -> LexEntryBuilder {
+        LexEntryBuilder {
+            lexicon: self,
+            phons: phons.iter().map(|phon| phon.to_string()).collect(),
+            feats: Vec::new(),
+            weight: 1.0,
+        }
+    }
+    
+    /// Add an already built item
+    pub fn item(mut self, item: LexItem) -> Self {
+        self.items.push(item);
+        self
+    }
+    
+    /// Finish the lexicon, with items in the order they were added
+    pub fn build(self) -> Vec<LexItem> {
+        self.items
+    }
+}
+
+impl FromIterator<LexItem> for LexiconBuilder {
+    fn from_iter<I: IntoIterator<Item = LexItem>>(iter: I) -> Self {
+        Self { items: iter.into_iter().collect() }
+    }
+}
+
+impl Extend<LexItem> for LexiconBuilder {
+    fn extend<I: IntoIterator<Item = LexItem>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+/// Entry under construction in a `LexiconBuilder`
+///
+/// Features are added in order, so `.cat(D).sel(N)` gives `[Cat(D), Sel(N)]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexEntryBuilder {
+    lexicon: LexiconBuilder,
+    phons: Vec<String>,
+    feats: Vec<Feature>,
+    weight: f64,
+}
+
+impl LexEntryBuilder {
+    /// Add any feature
+    pub fn feature(mut self, feature: Feature) -> Self {
+        self.feats.push(feature);
+        self
+    }
+    
+    /// Add a `Cat` feature
+    pub fn cat(self, cat: Category) -> Self {
+        self.feature(Feature::Cat(cat))
+    }
+    
+    /// Add a `Sel` feature
+    pub fn sel(self, cat: Category) -> Self {
+        self.feature(Feature::Sel(cat))
+    }
+    
+    /// Add a `SelAny` feature
+    pub fn sel_any(self, cats: &[Category]) -> Self {
+        self.feature(Feature::SelAny(cats.to_vec()))
+    }
+    
+    /// Add a `Pos` feature
+    pub fn pos(self, index: u8) -> Self {
+        self.feature(Feature::Pos(index))
+    }
+    
+    /// Add a `Neg` feature
+    pub fn neg(self, index: u8) -> Self {
+        self.feature(Feature::Neg(index))
+    }
+    
+    /// Add an `Adj` feature
+    pub fn adj(self, cat: Category) -> Self {
+        self.feature(Feature::Adj(cat))
+    }
+    
+    /// Add an `Agr` feature
+    pub fn agr(self, value: &str) -> Self {
+        self.feature(Feature::Agr(value.to_string()))
+    }
+    
+    /// Set the entry's weight
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is not positive and finite.
+    pub fn weight(mut self, weight: f64) -> Self {
+        assert!(is_valid_weight(weight), "lexical weight must be positive and finite, got {}", weight);
+        self.weight = weight;
+        self
+    }
+    
+    /// Finish the entry and return to the lexicon
+    pub fn done(self) -> LexiconBuilder {
+        let LexEntryBuilder { mut lexicon, phons, feats, weight } = self;
+        lexicon.items.extend(phons.iter().map(|phon| LexItem::new(phon, &feats).with_weight(weight)));
+        lexicon
+    }
+}
+
+/// Generate aⁿbⁿ pattern for testing recursion
+pub fn generate_an_bn(n: usize) -> String {
+    let mut symbols = Vec::with_capacity(2 * n);
+    for i in 0..2 * n {
+        symbols.push(if i < n { "a" } else { "b" });
+    }
+    symbols.join(" ")
+}
+
+/// Generate aⁿbⁿcⁿ, the standard non-context-free counting pattern
+pub fn generate_an_bn_cn(n: usize) -> String {
+    ["a", "b", "c"].iter()
+        .flat_map(|symbol| core::iter::repeat(*symbol).take(n))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The canonical length-`n` word over {a, b}: `a b a b ...`
+fn alternating_word(n: usize) -> Vec<&'static str> {
+    (0..n).map(|i| if i % 2 == 0 { "a" } else { "b" }).collect()
+}
+
+/// Generate ww (the copy language) for the canonical word w of length `n`
+pub fn generate_ww(n: usize) -> String {
+    let w = alternating_word(n);
+    w.iter().chain(&w).copied().collect::<Vec<_>>().join(" ")
+}
+
+/// Generate the even palindrome w wᴿ for the canonical word w of length `n`
+pub fn generate_palindrome(n: usize) -> String {
+    let w = alternating_word(n);
+    w.iter().chain(w.iter().rev()).copied().collect::<Vec<_>>().join(" ")
+}
+
+/// Lexicon for deriving aⁿbⁿ with the engine
+///
+/// A covert S is the center of the string. Each "a" selects an S and waits
+/// to move; each "b" selects one such phrase and attracts it to its left,
+/// giving `[[a S] [b t]]`, which is the S for the next "a" up.
+pub fn an_bn_lexicon() -> Vec<LexItem> {
+    vec![
+        LexItem::new("a", &[
+            Feature::Cat(Category::D),
+            Feature::Sel(Category::S),
+            Feature::Neg(1),
+        ]),
+        LexItem::new("b", &[
+            Feature::Cat(Category::S),
+            Feature::Sel(Category::D),
+            Feature::Pos(1),
+        ]),
+        LexItem::covert(&[Feature::Cat(Category::S)]),
+    ]
+}
+
+/// Derive aⁿbⁿ in one derivation over `an_bn_lexicon`
+///
+/// The workspace holds the n "a"s, the covert S and the n "b"s in that
+/// order, and merge is restricted to neighbours, so each level closes
+/// around the one inside it. The result linearizes to `generate_an_bn(n)`;
+/// for `n == 0` it is the covert S alone.
+pub fn derive_an_bn(n: usize) -> Result<SyntacticObject, DerivationError> {
+    let lexicon = an_bn_lexicon();
+    let (a, b) = (&lexicon[0], &lexicon[1]);
+    
+    let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT).with_adjacency();
+    for _ in 0..n {
+        workspace.add_lex(a);
+    }
+    workspace.add_covert(&lexicon);
+    for _ in 0..n {
+        workspace.add_lex(b);
+    }
+    
+    // Each level takes two merges and a move
+    derive(&mut workspace, DEFAULT_MAX_STEPS.max(3 * n))
+}
+
+/// Test if string matches aⁿbⁿ pattern
+pub fn is_an_bn_pattern(s: &str) -> bool {
+    let tokens: Vec<&str> = s.split_whitespace().collect();
+    if tokens.is_empty() {
+        return true; // ε case
+    }
+    
+    let n = tokens.len() / 2;
+    if tokens.len() != 2 * n {
+        return false;
+    }
+    
+    // Check first n tokens are 'a' and the last n are 'b'
+    tokens[..n].iter().all(|&t| t == "a") && tokens[n..].iter().all(|&t| t == "b")
+}
+
+/// Check if string follows aⁿbⁿcⁿ pattern
+pub fn is_an_bn_cn_pattern(s: &str) -> bool {
+    let tokens: Vec<&str> = s.split_whitespace().collect();
+    let n = tokens.len() / 3;
+    tokens.len() == 3 * n && tokens.join(" ") == generate_an_bn_cn(n)
+}
+
+/// Check if string is some word over {a, b} followed by a copy of itself
+pub fn is_ww_pattern(s: &str) -> bool {
+    let tokens: Vec<&str> = s.split_whitespace().collect();
+    let (w, copy) = tokens.split_at(tokens.len() / 2);
+    tokens.len() % 2 == 0 && w == copy && w.iter().all(|t| *t == "a" || *t == "b")
+}
+
+/// Check if string is an even-length palindrome over {a, b}
+pub fn is_palindrome_pattern(s: &str) -> bool {
+    let tokens: Vec<&str> = s.split_whitespace().collect();
+    tokens.len() % 2 == 0
+        && tokens.iter().all(|t| *t == "a" || *t == "b")
+        && tokens.iter().eq(tokens.iter().rev())
+}
+
+// ============================================================================
+// Grammar Analysis
+// ============================================================================
+
+/// Category feature of a lexical item, if it has one
+fn lex_category(item: &LexItem) -> Option<&Category> {
+    item.feats.iter().find_map(|f| match f {
+        Feature::Cat(cat) => Some(cat),
+        _ => None,
+    })
+}
+
+/// Categories selected by each of a lexical item's required selectors; a
+/// `SelAny` selector is satisfied by any one of its categories
+fn lex_selectors(item: &LexItem) -> impl Iterator<Item = &[Category]> {
+    item.feats.iter().filter(|f| f.is_selector()).map(Feature::selected)
+}
+
+/// Categories heading at least one finite phrase, i.e. having a lexical item
+/// whose selectors can all be satisfied (computed as a fixpoint)
+fn productive_categories(lexicon: &[LexItem]) -> Vec<Category> {
+    let mut productive: Vec<Category> = Vec::new();
+    
+    loop {
+        let mut changed = false;
+        for item in lexicon {
+            if let Some(cat) = lex_category(item) {
+                if !productive.contains(cat) && lex_selectors(item).all(|sel| sel.iter().any(|c| productive.contains(c))) {
+                    productive.push(cat.clone());
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return productive;
+        }
+    }
+}
+
+/// Selection edges `(head category, selected category)` contributed by
+/// lexical items that can actually be saturated
+fn productive_selection_edges(lexicon: &[LexItem], productive: &[Category]) -> Vec<(Category, Category)> {
+    let mut edges = Vec::new();
+    
+    for item in lexicon {
+        if let Some(cat) = lex_category(item) {
+            if lex_selectors(item).all(|sel| sel.iter().any(|c| productive.contains(c))) {
+                for sel in lex_selectors(item).flatten().filter(|c| productive.contains(c)) {
+                    let edge = (cat.clone(), sel.clone());
+                    if !edges.contains(&edge) {
+                        edges.push(edge);
+                    }
+                }
+            }
+        }
+    }
+    
+    edges
+}
+
+/// Check whether the grammar generates infinitely many strings from `start`
+///
+/// The language is infinite exactly when a productive category reachable
+/// from `start` can (transitively) select itself: such a cycle can be
+/// unrolled any number of times before taking a terminating branch.
+pub fn is_language_infinite(lexicon: &[LexItem], start: Category) -> bool {
+    let productive = productive_categories(lexicon);
+    if !productive.contains(&start) {
+        return false;
+    }
+    
+    let edges = productive_selection_edges(lexicon, &productive);
+    
+    fn has_cycle(
+        cat: &Category,
+        edges: &[(Category, Category)],
+        path: &mut Vec<Category>,
+        done: &mut Vec<Category>,
+    ) -> bool {
+        if path.contains(cat) {
+            return true;
+        }
+        if done.contains(cat) {
+            return false;
+        }
+        
+        path.push(cat.clone());
+        for (_, next) in edges.iter().filter(|(from, _)| from == cat) {
+            if has_cycle(next, edges, path, done) {
+                return true;
+            }
+        }
+        path.pop();
+        done.push(cat.clone());
+        false
+    }
+    
+    has_cycle(&start, &edges, &mut Vec::new(), &mut Vec::new())
+}
+
+/// Find pairs of lexical entries with identical feature bundles
+///
+/// Such entries behave identically in every derivation, differing at most
+/// in phon. Pairs `(i, j)` are reported with `i < j`, in index order.
+pub fn find_redundant_entries(lexicon: &[LexItem]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for i in 0..lexicon.len() {
+        for j in i + 1..lexicon.len() {
+            if lexicon[i].feats == lexicon[j].feats {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+/// Movement trigger that no derivable constituent below it can satisfy
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovementWarning {
+    /// Phonological form of the lexical item carrying the trigger
+    pub phon: String,
+    /// Movement index of the unsatisfiable `Pos` feature
+    pub index: u8,
+}
+
+impl fmt::Display for MovementWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' triggers movement +{} but nothing it can dominate carries -{}", self.phon, self.index, self.index)
+    }
+}
+
+/// Movement feature whose counterpart appears nowhere in the lexicon
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureWarning {
+    /// The unpaired `Pos(i)` or `Neg(i)`
+    pub feature: Feature,
+    /// Phonological forms of the items carrying it, in lexicon order
+    pub carriers: Vec<String>,
+}
+
+impl fmt::Display for FeatureWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let carriers: Vec<String> = self.carriers.iter().map(|phon| format!("'{}'", phon)).collect();
+        match self.feature {
+            Feature::Pos(i) => write!(f, "+{} on {} has no -{} anywhere in the lexicon", i, carriers.join(", "), i),
+            Feature::Neg(i) => write!(f, "-{} on {} has no +{} anywhere in the lexicon", i, carriers.join(", "), i),
+            _ => write!(f, "{:?} on {} is unpaired", self.feature, carriers.join(", ")),
+        }
+    }
+}
+
+/// Movement features with no opposite-polarity partner of the same index
+fn unpaired_movement_features(lexicon: &[LexItem]) -> Vec<FeatureWarning> {
+    let movement = |item: &LexItem| -> Vec<Feature> {
+        item.feats.iter()
+            .map(Feature::inner)
+            .filter(|f| f.movement_index().is_some())
+            .cloned()
+            .collect()
+    };
+    let present: Vec<Feature> = lexicon.iter().flat_map(movement).collect();
+    
+    let mut warnings: Vec<FeatureWarning> = Vec::new();
+    for item in lexicon {
+        for feature in movement(item) {
+            let partner = match feature {
+                Feature::Pos(i) => Feature::Neg(i),
+                Feature::Neg(i) => Feature::Pos(i),
+                _ => unreachable!("movement features are Pos or Neg"),
+            };
+            if present.contains(&partner) {
+                continue;
+            }
+            match warnings.iter_mut().find(|w| w.feature == feature) {
+                Some(warning) => warning.carriers.push(item.phon.clone()),
+                None => warnings.push(FeatureWarning { feature, carriers: vec![item.phon.clone()] }),
+            }
+        }
+    }
+    warnings
+}
+
+/// Categories an item can (transitively) select, i.e. that can occur below it
+fn categories_below(lexicon: &[LexItem], item: &LexItem) -> Vec<Category> {
+    let mut below: Vec<Category> = lex_selectors(item).flatten().cloned().collect();
+    let mut k = 0;
+    while k < below.len() {
+        let cat = below[k].clone();
+        for other in lexicon.iter().filter(|other| lex_category(other) == Some(&cat)) {
+            for sel in lex_selectors(other).flatten() {
+                if !below.contains(sel) {
+                    below.push(sel.clone());
+                }
+            }
+        }
+        k += 1;
+    }
+    below
+}
+
+/// Check that every `Pos(i)` trigger can reach a constituent carrying `Neg(i)`
+///
+/// A trigger is reachable when some lexical item whose category is
+/// (transitively) selectable below the triggering head carries the matching
+/// negative feature. Triggers failing this check can never fire and leave
+/// derivations stuck. `Optional` movement features are checked like the
+/// features they wrap.
+pub fn check_movement_reachability(lexicon: &[LexItem]) -> Vec<MovementWarning> {
+    let mut warnings = Vec::new();
+    
+    for item in lexicon {
+        let below = categories_below(lexicon, item);
+        for index in item.feats.iter().map(Feature::inner).filter(|f| f.is_positive()).filter_map(Feature::movement_index) {
+            let reachable = lexicon.iter().any(|other| {
+                lex_category(other).is_some_and(|cat| below.contains(cat))
+                    && other.feats.iter().any(|f| *f.inner() == Feature::Neg(index))
+            });
+            if !reachable {
+                warnings.push(MovementWarning { phon: item.phon.clone(), index });
+            }
+        }
+    }
+    
+    warnings
+}
+
+/// Whether a selection cycle can bottom out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecursionKind {
+    /// Every category on the cycle heads some finite phrase, so the
+    /// recursion can stop at a base case (as `VOLTAGE_SPIKE` ends a chain
+    /// of telemetry states)
+    Productive,
+    /// Some category on the cycle can never be completed, so a phrase that
+    /// enters the cycle keeps selecting until the derivation runs out of
+    /// material or steps
+    Unbounded,
+}
+
+/// A category that can (transitively) select itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminationWarning {
+    /// Categories along the cycle, each selecting the next and the last
+    /// selecting the first
+    pub cycle: Vec<Category>,
+    /// Whether the cycle can bottom out
+    pub kind: RecursionKind,
+}
+
+impl fmt::Display for TerminationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path: Vec<String> = self.cycle.iter().chain(self.cycle.first()).map(|cat| format!("{:?}", cat)).collect();
+        match self.kind {
+            RecursionKind::Productive => write!(f, "selection cycle {} is productive recursion with a base case", path.join(" -> ")),
+            RecursionKind::Unbounded => write!(f, "selection cycle {} has no base case and never terminates", path.join(" -> ")),
+        }
+    }
+}
+
+/// Every elementary cycle of the selection graph over required selectors
+///
+/// Each cycle is reported once, starting from the category whose first head
+/// comes earliest in the lexicon.
+fn selection_cycles(lexicon: &[LexItem]) -> Vec<TerminationWarning> {
+    let mut heads: Vec<&Category> = Vec::new();
+    for cat in lexicon.iter().filter_map(lex_category) {
+        if !heads.contains(&cat) {
+            heads.push(cat);
+        }
+    }
+    let selects = |from: &Category, to: &Category| {
+        lexicon.iter()
+            .filter(|item| lex_category(item) == Some(from))
+            .any(|item| lex_selectors(item).flatten().any(|cat| cat == to))
+    };
+    
+    // Extend `path` through heads after its first, closing it when an edge
+    // leads back to the first
+    fn extend(
+        heads: &[&Category],
+        selects: &dyn Fn(&Category, &Category) -> bool,
+        path: &mut Vec<usize>,
+        cycles: &mut Vec<Vec<Category>>,
+    ) {
+        let (start, last) = (path[0], path[path.len() - 1]);
+        if selects(heads[last], heads[start]) {
+            cycles.push(path.iter().map(|&k| heads[k].clone()).collect());
+        }
+        for next in start + 1..heads.len() {
+            if !path.contains(&next) && selects(heads[last], heads[next]) {
+                path.push(next);
+                extend(heads, selects, path, cycles);
+                path.pop();
+            }
+        }
+    }
+    
+    let mut cycles = Vec::new();
+    for start in 0..heads.len() {
+        extend(&heads, &selects, &mut vec![start], &mut cycles);
+    }
+    
+    let productive = productive_categories(lexicon);
+    cycles.into_iter().map(|cycle| {
+        let kind = if cycle.iter().all(|cat| productive.contains(cat)) {
+            RecursionKind::Productive
+        } else {
+            RecursionKind::Unbounded
+        };
+        TerminationWarning { cycle, kind }
+    }).collect()
+}
+
+/// Every phon that can begin a phrase of category `cat`
+///
+/// Heads precede what they select, so a phrase begins with its own head
+/// unless movement has fronted something from below: a head carrying
+/// `Pos(i)` adds the first terminals of every category it can select
+/// (transitively) whose items carry the matching `Neg(i)`. Like FIRST sets
+/// for context-free grammars, this over-approximates: every phrase of such
+/// a category counts, whether or not it can actually move.
+pub fn first_terminals(lexicon: &[LexItem], cat: Category) -> BTreeSet<String> {
+    fn collect(lexicon: &[LexItem], cat: &Category, seen: &mut Vec<Category>, first: &mut BTreeSet<String>) {
+        if seen.contains(cat) {
+            return;
+        }
+        seen.push(cat.clone());
+        
+        for item in lexicon.iter().filter(|item| lex_category(item) == Some(cat)) {
+            first.insert(item.phon.clone());
+            
+            let triggers: Vec<u8> = item.feats.iter()
+                .filter(|f| f.inner().is_positive())
+                .filter_map(|f| f.inner().movement_index())
+                .collect();
+            if triggers.is_empty() {
+                continue;
+            }
+            
+            for below in categories_below(lexicon, item) {
+                let moves = lexicon.iter().any(|other| {
+                    lex_category(other) == Some(&below)
+                        && other.feats.iter().any(|f| triggers.iter().any(|&i| f.inner() == &Feature::Neg(i)))
+                });
+                if moves {
+                    collect(lexicon, &below, seen, first);
+                }
+            }
+        }
+    }
+    
+    let mut first = BTreeSet::new();
+    collect(lexicon, &cat, &mut Vec::new(), &mut first);
+    first
+}
+
+/// Every complete tree derivable from at most `max_items` lexical items
+///
+/// Each multiset of lexical items is loaded into a workspace and every
+/// derivation from it is explored, so word order is whatever the grammar
+/// linearizes rather than the order items were loaded in.
+fn derivable_trees(lexicon: &[LexItem], max_items: usize) -> Vec<SyntacticObject> {
+    fn visit(
+        lexicon: &[LexItem],
+        from: usize,
+        remaining: usize,
+        workspace: &mut Workspace,
+        trees: &mut Vec<SyntacticObject>,
+    ) {
+        if !workspace.items.is_empty() {
+            all_derivations(workspace, DEFAULT_MAX_STEPS, usize::MAX, trees);
+        }
+        if remaining == 0 {
+            return;
+        }
+        
+        for k in from..lexicon.len() {
+            workspace.add_lex(&lexicon[k]);
+            visit(lexicon, k, remaining - 1, workspace, trees);
+            workspace.items.pop();
+        }
+    }
+    
+    let mut trees = Vec::new();
+    visit(lexicon, 0, max_items, &mut Workspace::new(DEFAULT_MEMORY_LIMIT), &mut trees);
+    trees
+}
+
+/// Every string of category `start` derivable from at most `max_len`
+/// lexical items, sorted and without duplicates
+fn language_upto(lexicon: &[LexItem], start: &Category, max_len: usize) -> Vec<String> {
+    let mut strings: Vec<String> = derivable_trees(lexicon, max_len).iter()
+        .filter(|t| t.label == *start)
+        .map(|t| t.linearize())
+        .collect();
+    strings.sort();
+    strings.dedup();
+    strings
+}
+
+/// Where a phrase is pronounced, for the FIRST / LAST sets of `can_be_adjacent`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Site {
+    /// A phrase of this category where it was merged
+    InSitu(Category),
+    /// A phrase fronted by `Pos(i)` to the edge of the trigger's phrase
+    Moved(u8),
+    /// An adjunct to a phrase of this category
+    Adjunct(Category),
+}
+
+/// One piece of surface material in a phrase
+#[derive(Debug, Clone)]
+enum Piece {
+    /// A head's own phon; empty for a covert head
+    Word(String),
+    /// A phrase pronounced at any one of these sites, or nowhere if the flag
+    /// is set (an optional selector, or a selectee that can move away)
+    Phrase(Vec<Site>, bool),
+}
+
+/// Whether a run of material can be silent, and the phons it can begin and
+/// end with
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Edges {
+    nullable: bool,
+    first: BTreeSet<String>,
+    last: BTreeSet<String>,
+}
+
+impl Edges {
+    fn of(piece: &Piece, table: &BTreeMap<Site, Edges>) -> Edges {
+        match piece {
+            Piece::Word(phon) if phon.is_empty() => Edges { nullable: true, ..Edges::default() },
+            Piece::Word(phon) => Edges {
+                nullable: false,
+                first: BTreeSet::from([phon.clone()]),
+                last: BTreeSet::from([phon.clone()]),
+            },
+            Piece::Phrase(sites, may_vanish) => {
+                let mut edges = Edges { nullable: *may_vanish, ..Edges::default() };
+                for site in sites.iter().filter_map(|site| table.get(site)) {
+                    edges.nullable |= site.nullable;
+                    edges.first.extend(site.first.iter().cloned());
+                    edges.last.extend(site.last.iter().cloned());
+                }
+                edges
+            }
+        }
+    }
+    
+    /// Edges of a run of pieces, left to right
+    fn of_run(pieces: &[Piece], table: &BTreeMap<Site, Edges>) -> Edges {
+        let mut run = Edges { nullable: true, ..Edges::default() };
+        for piece in pieces {
+            let next = Edges::of(piece, table);
+            if run.nullable {
+                run.first.extend(next.first.iter().cloned());
+            }
+            if next.nullable {
+                run.last.extend(next.last);
+            } else {
+                run.last = next.last;
+            }
+            run.nullable &= next.nullable;
+        }
+        run
+    }
+}
+
+/// The phrases of a lexicon as runs of pieces, one per lexical item and
+/// adjunction site
+///
+/// An item's phrase is its fronted movers, then its head, then one phrase
+/// per selector in selection order. An item carrying `Neg(i)` is pronounced
+/// only where it moves to, so its phrase belongs to `Site::Moved(i)` and its
+/// merge position may stay silent. Adjuncts precede their host.
+fn surface_rules(lexicon: &[LexItem]) -> Vec<(Site, Vec<Piece>)> {
+    let movers = |cat: &Category| lexicon.iter()
+        .any(|item| lex_category(item) == Some(cat) && item.feats.iter().any(|f| f.inner().is_negative()));
+    
+    let mut rules = Vec::new();
+    for item in lexicon {
+        let mut pieces: Vec<Piece> = item.feats.iter()
+            .filter(|f| f.inner().is_positive())
+            .filter_map(|f| f.inner().movement_index())
+            .map(|i| Piece::Phrase(vec![Site::Moved(i)], false))
+            .collect();
+        pieces.push(Piece::Word(item.phon.clone()));
+        for f in item.feats.iter().filter(|f| f.inner().is_selector()) {
+            let cats = f.inner().selected();
+            let vanishes = f.is_optional() || cats.iter().any(movers);
+            pieces.push(Piece::Phrase(cats.iter().cloned().map(Site::InSitu).collect(), vanishes));
+        }
+        
+        let mut sites: Vec<Site> = item.feats.iter().filter_map(|f| match f.inner() {
+            Feature::Neg(i) => Some(Site::Moved(*i)),
+            Feature::Adj(cat) => Some(Site::Adjunct(cat.clone())),
+            _ => None,
+        }).collect();
+        if !sites.iter().any(|site| matches!(site, Site::Moved(_))) {
+            sites.extend(lex_category(item).cloned().map(Site::InSitu));
+        }
+        
+        rules.extend(sites.into_iter().map(|site| (site, pieces.clone())));
+    }
+    
+    let hosts: BTreeSet<&Category> = lexicon.iter().flat_map(|item| &item.feats).filter_map(|f| match f.inner() {
+        Feature::Adj(cat) => Some(cat),
+        _ => None,
+    }).collect();
+    for cat in hosts {
+        let host = Site::InSitu(cat.clone());
+        rules.push((host.clone(), vec![Piece::Phrase(vec![Site::Adjunct(cat.clone())], false), Piece::Phrase(vec![host], false)]));
+    }
+    rules
+}
+
+/// Check whether any derivation can put `a` immediately before `b`
+///
+/// The lexicon is read as a context-free grammar over surface positions
+/// (see `surface_rules`), including the landing sites of movement, and the
+/// FIRST and LAST phon sets of every phrase are computed as a fixpoint. Two
+/// phons can be adjacent when one piece of some phrase can end with `a` and
+/// the next pronounced piece can begin with `b`. Like `first_terminals` this
+/// over-approximates: it ignores whether the derivation around the phrase
+/// can complete, but it never misses an adjacency, however long the
+/// sentences that produce it.
+pub fn can_be_adjacent(a: &str, b: &str, lexicon: &[LexItem]) -> bool {
+    let rules = surface_rules(lexicon);
+    
+    let mut table: BTreeMap<Site, Edges> = BTreeMap::new();
+    loop {
+        let mut changed = false;
+        for (site, pieces) in &rules {
+            let run = Edges::of_run(pieces, &table);
+            let entry = table.entry(site.clone()).or_default();
+            let merged = Edges {
+                nullable: entry.nullable || run.nullable,
+                first: entry.first.union(&run.first).cloned().collect(),
+                last: entry.last.union(&run.last).cloned().collect(),
+            };
+            if merged != *entry {
+                *entry = merged;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    
+    rules.iter().any(|(_, pieces)| {
+        (0..pieces.len()).any(|i| {
+            let left = Edges::of(&pieces[i], &table);
+            if !left.last.contains(a) {
+                return false;
+            }
+            pieces[i + 1..].iter()
+                .map(|piece| Edges::of(piece, &table))
+                .scan(true, |open, next| {
+                    let reachable = *open;
+                    *open = next.nullable;
+                    reachable.then_some(next)
+                })
+                .any(|next| next.first.contains(b))
+        })
+    })
+}
+
+/// Find strings of category `start` accepted by exactly one of two grammars
+///
+/// Both languages are enumerated over derivations of at most `max_len`
+/// lexical items and their symmetric difference is returned, shortest
+/// strings first. An empty result means the grammars agree up to that size.
+pub fn distinguishing_examples(lex_a: &[LexItem], lex_b: &[LexItem], start: Category, max_len: usize) -> Vec<String> {
+    let lang_a = language_upto(lex_a, &start, max_len);
+    let lang_b = language_upto(lex_b, &start, max_len);
+    
+    let mut examples: Vec<String> = lang_a.iter()
+        .filter(|s| !lang_b.contains(s))
+        .chain(lang_b.iter().filter(|s| !lang_a.contains(s)))
+        .cloned()
+        .collect();
+    examples.sort_by(|x, y| x.split_whitespace().count().cmp(&y.split_whitespace().count()).then_with(|| x.cmp(y)));
+    examples
+}
+
+/// Largest number of constituents `surface_orders` will arrange
+pub const MAX_SURFACE_CONSTITUENTS: usize = 6;
+
+/// Every surface token order the grammar can build from a set of phrases
+///
+/// All merge and move orders over the constituents are explored, and the
+/// distinct linearizations of the complete results are returned, sorted.
+/// More than `MAX_SURFACE_CONSTITUENTS` constituents yield no orders.
+pub fn surface_orders(constituents: &[SyntacticObject]) -> Vec<Vec<String>> {
+    if constituents.is_empty() || constituents.len() > MAX_SURFACE_CONSTITUENTS {
+        return Vec::new();
+    }
+    
+    let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT);
+    workspace.items = constituents.to_vec();
+    let mut trees = Vec::new();
+    all_derivations(&workspace, DEFAULT_MAX_STEPS, usize::MAX, &mut trees);
+    
+    let mut orders: Vec<Vec<String>> = trees.iter()
+        .map(|tree| tree.linearize().split_whitespace().map(str::to_string).collect())
+        .collect();
+    orders.sort();
+    orders.dedup();
+    orders
+}
+
+/// Count how often each subtree shape occurs across a corpus of parses
+///
+/// Every node of every tree contributes its `shape()`, so the keys are the
+/// structural configurations the corpus exercises; grammar rules absent from
+/// the report are untested by the corpus.
+pub fn collect_subtree_shapes(trees: &[SyntacticObject]) -> BTreeMap<String, usize> {
+    fn visit(obj: &SyntacticObject, counts: &mut BTreeMap<String, usize>) {
+        *counts.entry(obj.shape()).or_insert(0) += 1;
+        for child in &obj.children {
+            visit(child, counts);
+        }
+    }
+    
+    let mut counts = BTreeMap::new();
+    for tree in trees {
+        visit(tree, &mut counts);
+    }
+    counts
+}
+
+/// Enumerate the ways of filling a category skeleton with lexical items
+///
+/// Leaves of `skeleton` give only a category label; each is filled with a
+/// lexical item of that category, and every binary node is rebuilt by
+/// `merge` so that only derivable fillings with the skeleton's labels are
+/// kept. Assignments are tried in lexicon order and at most `limit` trees
+/// are returned.
+pub fn lexicalize(skeleton: &SyntacticObject, lexicon: &[LexItem], limit: usize) -> Vec<SyntacticObject> {
+    fn leaves<'a>(obj: &'a SyntacticObject, out: &mut Vec<&'a SyntacticObject>) {
+        if obj.children.is_empty() {
+            out.push(obj);
+        }
+        for child in &obj.children {
+            leaves(child, out);
+        }
+    }
+    
+    fn fill<'a>(obj: &SyntacticObject, items: &mut impl Iterator<Item = &'a LexItem>) -> Option<SyntacticObject> {
+        match obj.children.as_slice() {
+            [] => items.next().map(SyntacticObject::from_lex),
+            [left, right] => {
+                let left = fill(left, items)?;
+                let right = fill(right, items)?;
+                merge(left, right).ok().filter(|merged| merged.label == obj.label)
+            }
+            _ => None,
+        }
+    }
+    
+    let mut slots = Vec::new();
+    leaves(skeleton, &mut slots);
+    let candidates: Vec<Vec<&LexItem>> = slots.iter()
+        .map(|slot| lexicon.iter().filter(|item| lex_category(item) == Some(&slot.label)).collect())
+        .collect();
+    
+    let mut results = Vec::new();
+    if candidates.iter().any(|c| c.is_empty()) {
+        return results;
+    }
+    
+    // Odometer over one candidate index per leaf, rightmost leaf fastest
+    let mut choice = vec![0; slots.len()];
+    while results.len() < limit {
+        let mut items = choice.iter().zip(&candidates).map(|(&k, c)| c[k]);
+        if let Some(tree) = fill(skeleton, &mut items) {
+            results.push(tree);
+        }
+        
+        let mut pos = choice.len();
+        loop {
+            if pos == 0 {
+                return results;
+            }
+            pos -= 1;
+            choice[pos] += 1;
+            if choice[pos] < candidates[pos].len() {
+                break;
+            }
+            choice[pos] = 0;
+        }
+    }
+    
+    results
+}
+
+// ============================================================================
+// Tokenization
+// ============================================================================
+
+/// Splits input into tokens on a configurable set of delimiters
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tokenizer {
+    /// Characters separating tokens
+    pub delimiters: Vec<char>,
+    /// Whether any whitespace also separates tokens
+    pub split_whitespace: bool,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self {
+            delimiters: Vec::new(),
+            split_whitespace: true,
+        }
+    }
+}
+
+impl Tokenizer {
+    /// Create tokenizer splitting on whitespace and the given delimiters
+    pub fn with_delimiters(delimiters: &[char]) -> Self {
+        Self {
+            delimiters: delimiters.to_vec(),
+            split_whitespace: true,
+        }
+    }
+    
+    /// Check if a character separates tokens
+    pub fn is_delimiter(&self, c: char) -> bool {
+        (self.split_whitespace && c.is_whitespace()) || self.delimiters.contains(&c)
+    }
+    
+    /// Split input into non-empty tokens
+    pub fn tokenize<'a>(&self, input: &'a str) -> Vec<&'a str> {
+        input.split(|c| self.is_delimiter(c))
+            .filter(|token| !token.is_empty())
+            .collect()
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Default workspace memory limit used by the parsing entry points (4KB)
+const DEFAULT_MEMORY_LIMIT: usize = 4096;
+
+/// Default derivation step budget used by the parsing entry points
+const DEFAULT_MAX_STEPS: usize = 100;
+
+/// Default cap on input length used by the parsing entry points
+const DEFAULT_MAX_TOKENS: usize = 1024;
+
+/// Parse sentence using Minimalist Grammar
+///
+/// Uses a 4096-byte workspace and a budget of 100 derivation steps; see
+/// `parse_sentence_with_limits` to size them for longer input.
+pub fn parse_sentence(sentence: &str, lexicon: &[LexItem]) -> Result<SyntacticObject, DerivationError> {
+    parse_sentence_with_limits(sentence, lexicon, DEFAULT_MEMORY_LIMIT, DEFAULT_MAX_STEPS)
+}
+
+/// Parse sentence with an explicit workspace memory limit and step budget
+///
+/// Every merge combines two workspace items into one, so a successful
+/// derivation of `n` tokens takes exactly `n - 1` merge steps plus one step
+/// per movement, and `max_steps` must be at least that. Running out of
+/// steps is reported as `NoValidOperations`, like any other stuck
+/// derivation. Memory grows with the tree: under the default node cost each
+/// of the `2n - 1` nodes weighs 1 plus its phonology bytes and unchecked
+/// features, and the limit is checked before every step.
+pub fn parse_sentence_with_limits(
+    sentence: &str,
+    lexicon: &[LexItem],
+    memory_limit: usize,
+    max_steps: usize,
+) -> Result<SyntacticObject, DerivationError> {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    check_token_count(&tokens, DEFAULT_MAX_TOKENS)?;
+    parse_tokens_with_limit(&tokens, lexicon, memory_limit, max_steps).0
+        .map_err(|e| e.located_in(sentence, &tokens))
+}
+
+/// Parse sentence, holding heads to the arities of a category side table
+///
+/// The table is installed as the workspace's `arity`, so a merge that would
+/// complete a head with the wrong number of arguments is never made.
+pub fn parse_with_arity(
+    sentence: &str,
+    lexicon: &[LexItem],
+    arity: &BTreeMap<Category, usize>,
+) -> Result<SyntacticObject, DerivationError> {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    check_token_count(&tokens, DEFAULT_MAX_TOKENS)?;
+    let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT);
+    workspace.arity = arity.clone();
+    load_tokens(&mut workspace, &tokens, lexicon)
+        .and_then(|()| derive(&mut workspace, DEFAULT_MAX_STEPS))
+        .map_err(|e| e.located_in(sentence, &tokens))
+}
+
+/// Parse sentence and also return the final workspace for inspection
+///
+/// The workspace is returned on success and failure alike, so stranded
+/// constituents and the step count of a failed parse can be examined.
+/// On an unknown token it holds the items loaded before that token.
+pub fn parse_sentence_debug(sentence: &str, lexicon: &[LexItem]) -> (Result<SyntacticObject, DerivationError>, Workspace) {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    let (result, workspace) = parse_tokens_debug(&tokens, lexicon);
+    (result.map_err(|e| e.located_in(sentence, &tokens)), workspace)
+}
+
+/// Parse sentence, refusing inputs longer than `max_tokens` up front
+///
+/// Over-long input is rejected with `InputTooLong` before any lexicon
+/// lookup or derivation work, which bounds the cost of untrusted input.
+pub fn parse_with_max_tokens(sentence: &str, lexicon: &[LexItem], max_tokens: usize) -> Result<SyntacticObject, DerivationError> {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    check_token_count(&tokens, max_tokens)?;
+    parse_tokens_with_limit(&tokens, lexicon, DEFAULT_MEMORY_LIMIT, DEFAULT_MAX_STEPS).0
+        .map_err(|e| e.located_in(sentence, &tokens))
+}
+
+/// Reject token sequences longer than `limit`
+fn check_token_count(tokens: &[&str], limit: usize) -> Result<(), DerivationError> {
+    if tokens.len() > limit {
+        return Err(DerivationError::InputTooLong { tokens: tokens.len(), limit });
+    }
+    Ok(())
+}
+
+/// Parse input split into tokens by a custom `Tokenizer`
+pub fn parse_with_tokenizer(input: &str, lexicon: &[LexItem], tokenizer: &Tokenizer) -> Result<SyntacticObject, DerivationError> {
+    let tokens = tokenizer.tokenize(input);
+    parse_tokens_debug(&tokens, lexicon).0.map_err(|e| e.located_in(input, &tokens))
+}
+
+/// How `parse_sentence_with` treats tokens missing from the lexicon
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum UnknownTokenPolicy {
+    /// Fail with `UnknownToken` on the first unknown token
+    #[default]
+    Fail,
+    /// Drop unknown tokens and parse the rest
+    Skip,
+    /// Treat each unknown token as a bare item of this category
+    Wildcard(Category),
+    /// Fail with `UnknownTokens` listing every unknown token at once
+    Collect,
+}
+
+/// Options for `parse_sentence_with`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseOptions {
+    /// Handling of tokens missing from the lexicon
+    pub unknown_tokens: UnknownTokenPolicy,
+}
+
+/// Parse sentence with configurable handling of out-of-lexicon tokens
+///
+/// With the default options this behaves like `parse_sentence`.
+pub fn parse_sentence_with(sentence: &str, lexicon: &[LexItem], options: &ParseOptions) -> Result<SyntacticObject, DerivationError> {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    check_token_count(&tokens, DEFAULT_MAX_TOKENS)?;
+    if lexicon.is_empty() {
+        return Err(DerivationError::EmptyLexicon);
+    }
+    
+    let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT);
+    let mut unknown: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        if let Some((lex_item, len)) = match_entry(lexicon, &tokens, index) {
+            workspace.add_lex(lex_item);
+            index += len;
+            continue;
+        }
+        let token = tokens[index];
+        index += 1;
+        match options.unknown_tokens {
+            UnknownTokenPolicy::Fail => {
+                return Err(DerivationError::unknown_token(&tokens, index - 1).located_in(sentence, &tokens));
+            }
+            UnknownTokenPolicy::Skip => {}
+            UnknownTokenPolicy::Wildcard(ref cat) => {
+                workspace.add_lex(&LexItem::new(token, &[Feature::Cat(cat.clone())]));
+            }
+            UnknownTokenPolicy::Collect => {
+                if !unknown.iter().any(|seen| seen == token) {
+                    unknown.push(token.to_string());
+                }
+            }
+        }
+    }
+    
+    if !unknown.is_empty() {
+        return Err(DerivationError::UnknownTokens(unknown));
+    }
+    workspace.add_covert(lexicon);
+    derive(&mut workspace, DEFAULT_MAX_STEPS)
+}
+
+/// Load pre-split tokens into a fresh workspace and derive
+fn parse_tokens_debug(tokens: &[&str], lexicon: &[LexItem]) -> (Result<SyntacticObject, DerivationError>, Workspace) {
+    if let Err(e) = check_token_count(tokens, DEFAULT_MAX_TOKENS) {
+        return (Err(e), Workspace::new(DEFAULT_MEMORY_LIMIT));
+    }
+    parse_tokens_with_limit(tokens, lexicon, DEFAULT_MEMORY_LIMIT, DEFAULT_MAX_STEPS)
+}
+
+/// Like `parse_tokens_debug`, with explicit memory and step limits
+fn parse_tokens_with_limit(
+    tokens: &[&str],
+    lexicon: &[LexItem],
+    memory_limit: usize,
+    max_steps: usize,
+) -> (Result<SyntacticObject, DerivationError>, Workspace) {
+    let mut workspace = Workspace::new(memory_limit);
+    if let Err(e) = load_tokens(&mut workspace, tokens, lexicon) {
+        return (Err(e), workspace);
+    }
+    
+    let result = derive(&mut workspace, max_steps);
+    (result, workspace)
+}
+
+/// Every lexical entry pronounced `phon`, in lexicon order
+pub fn lookup_all<'a>(lexicon: &'a [LexItem], phon: &str) -> Vec<&'a LexItem> {
+    lexicon.iter().filter(|item| item.phon == phon).collect()
+}
+
+/// Lexical entry pronounced as the most tokens from `tokens[start]` on,
+/// with the number of tokens it spans
+///
+/// A multi-word phon such as `kick the bucket` matches its words as
+/// consecutive tokens. The longest match wins, so an idiom takes precedence
+/// over its first word; equally long matches go to the first in lexicon
+/// order. Covert items match nothing.
+fn match_entry<'a>(lexicon: &'a [LexItem], tokens: &[&str], start: usize) -> Option<(&'a LexItem, usize)> {
+    let rest = &tokens[start..];
+    let mut best: Option<(&LexItem, usize)> = None;
+    for item in lexicon {
+        let len = item.phon.split_whitespace().count();
+        let matches = len > 0
+            && len <= rest.len()
+            && item.phon.split_whitespace().zip(rest).all(|(word, token)| word == *token);
+        if matches && best.map_or(true, |(_, best_len)| len > best_len) {
+            best = Some((item, len));
+        }
+    }
+    best
+}
+
+/// Whether `words` are the first words of a lexical entry with more words
+fn starts_longer_entry(lexicon: &[LexItem], words: &[&str]) -> bool {
+    lexicon.iter().any(|item| {
+        let phon: Vec<&str> = item.phon.split_whitespace().collect();
+        phon.len() > words.len() && phon.starts_with(words)
+    })
+}
+
+/// Every choice of one lexical entry per token, in lexicon order, stopping
+/// after `limit` choices
+///
+/// Tokens are grouped into words as `match_entry` does, and each group may
+/// take any entry with the same phon.
+fn lexical_readings<'a>(tokens: &[&str], lexicon: &'a [LexItem], limit: usize) -> Result<Vec<Vec<&'a LexItem>>, DerivationError> {
+    if lexicon.is_empty() {
+        return Err(DerivationError::EmptyLexicon);
+    }
+    
+    let mut readings: Vec<Vec<&LexItem>> = vec![Vec::new()];
+    let mut index = 0;
+    while index < tokens.len() {
+        let Some((longest, len)) = match_entry(lexicon, tokens, index) else {
+            return Err(DerivationError::unknown_token(tokens, index));
+        };
+        let candidates = lookup_all(lexicon, &longest.phon);
+        index += len;
+        readings = readings.iter()
+            .flat_map(|items| candidates.iter().map(move |&item| {
+                let mut items = items.clone();
+                items.push(item);
+                items
+            }))
+            .take(limit)
+            .collect();
+    }
+    
+    Ok(readings)
+}
+
+/// Add the lexical item for each token to the workspace
+///
+/// Consecutive tokens forming a multi-word phon load as one item, as
+/// `match_entry` picks them. A token with several entries gets the first one
+/// in lexicon order; only `parse_all` and `derive_weighted` consider the
+/// others.
+fn load_tokens(workspace: &mut Workspace, tokens: &[&str], lexicon: &[LexItem]) -> Result<(), DerivationError> {
+    if lexicon.is_empty() {
+        return Err(DerivationError::EmptyLexicon);
+    }
+    
+    let mut index = 0;
+    while index < tokens.len() {
+        let (lex_item, len) = match_entry(lexicon, tokens, index)
+            .ok_or_else(|| DerivationError::unknown_token(tokens, index))?;
+        workspace.add_lex(lex_item);
+        index += len;
+    }
+    workspace.add_covert(lexicon);
+    
+    Ok(())
+}
+
+/// Parser that consumes tokens one at a time
+///
+/// Each `feed` loads one lexical item and immediately runs every merge or
+/// move that applies, so the workspace always holds the partial analysis of
+/// the stream so far; it is a complete parse whenever
+/// `current_state().is_successful()`. Items still waiting for material that
+/// has not arrived yet stay in the workspace rather than failing the feed.
+/// The lexicon's covert items are loaded along with the first token.
+///
+/// Tokens that begin a multi-word entry are held back until the entry is
+/// complete or the next token rules it out, and are then loaded as
+/// `parse_sentence` would group them. Call `finish` at the end of the
+/// stream to load whatever is still held back.
+#[derive(Debug, Clone)]
+pub struct IncrementalParser<'a> {
+    lexicon: &'a [LexItem],
+    workspace: Workspace,
+    tokens_fed: usize,
+    bytes_fed: usize,
+    pending: Vec<String>,
+}
+
+impl<'a> IncrementalParser<'a> {
+    /// Start an empty parse over `lexicon`
+    pub fn new(lexicon: &'a [LexItem]) -> Self {
+        Self {
+            lexicon,
+            workspace: Workspace::new(DEFAULT_MEMORY_LIMIT),
+            tokens_fed: 0,
+            bytes_fed: 0,
+            pending: Vec::new(),
+        }
+    }
+    
+    /// Add the next token and derive as far as possible
+    ///
+    /// Fails with `UnknownToken` if the token is not in the lexicon, or with
+    /// any error a derivation step raises. An unknown token is dropped, and
+    /// when it ends a run of held-back tokens, the ones before it are still
+    /// loaded. Every token fed counts towards the stream positions reported
+    /// in `UnknownToken`, including unknown ones.
+    pub fn feed(&mut self, token: &str) -> Result<(), DerivationError> {
+        self.push(token)?;
+        self.settle(false)
+    }
+    
+    /// Load the tokens held back as the start of a multi-word entry that
+    /// never completed, and derive as far as possible
+    pub fn finish(&mut self) -> Result<(), DerivationError> {
+        self.settle(true)
+    }
+    
+    /// Load every settled item from the buffer, deriving after each
+    fn settle(&mut self, at_end: bool) -> Result<(), DerivationError> {
+        while let Some(item) = self.next_item(at_end)? {
+            self.load(item);
+            for _ in 0..DEFAULT_MAX_STEPS {
+                if !self.advance()? {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Buffer `token` without loading it
+    fn push(&mut self, token: &str) -> Result<(), DerivationError> {
+        if self.lexicon.is_empty() {
+            return Err(DerivationError::EmptyLexicon);
+        }
+        self.tokens_fed += 1;
+        self.bytes_fed += token.len() + 1;
+        self.pending.push(token.to_string());
+        Ok(())
+    }
+    
+    /// Take the lexical item for the tokens at the front of the buffer
+    ///
+    /// Returns `None` while the buffer is empty or, unless `at_end`, could
+    /// still grow into a longer multi-word entry.
+    fn next_item(&mut self, at_end: bool) -> Result<Option<&'a LexItem>, DerivationError> {
+        let words: Vec<&str> = self.pending.iter().map(String::as_str).collect();
+        if words.is_empty() || (!at_end && starts_longer_entry(self.lexicon, &words)) {
+            return Ok(None);
+        }
+        let index = self.tokens_fed - words.len();
+        let byte_offset = self.bytes_fed - words.iter().map(|word| word.len() + 1).sum::<usize>();
+        
+        match match_entry(self.lexicon, &words, 0) {
+            Some((item, len)) => {
+                self.pending.drain(..len);
+                Ok(Some(item))
+            }
+            None => {
+                let token = self.pending.remove(0);
+                Err(DerivationError::UnknownToken { token, index, byte_offset })
+            }
+        }
+    }
+    
+    /// Add `item` to the workspace
+    fn load(&mut self, item: &LexItem) {
+        // Covert heads join with the first word, as `load_tokens` adds them
+        // once per sentence
+        let first = self.workspace.items.is_empty();
+        self.workspace.add_lex(item);
+        if first {
+            self.workspace.add_covert(self.lexicon);
+        }
+    }
+    
+    /// Run one derivation step, returning whether another may apply
+    fn advance(&mut self) -> Result<bool, DerivationError> {
+        if self.workspace.is_successful() {
+            return Ok(false);
+        }
+        match step(&mut self.workspace) {
+            Ok(()) => Ok(true),
+            Err(DerivationError::NoValidOperations) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Workspace holding the analysis of the tokens fed so far
+    pub fn current_state(&self) -> &Workspace {
+        &self.workspace
+    }
+}
+
+/// `IncrementalParser` for async telemetry pipelines
+///
+/// `feed` runs the same derivation as `IncrementalParser::feed`, but yields
+/// to the executor between steps, so a long derivation never blocks other
+/// tasks. Every adjacent pair of events is also checked as it is loaded, and
+/// each anomaly `find_mission_log_anomalies` would report for the events fed
+/// so far is sent on the channel returned by `new`. An event is one lexical
+/// item, so the words of a multi-word entry are checked together.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct AsyncIncrementalParser<'a> {
+    parser: IncrementalParser<'a>,
+    anomalies: tokio::sync::mpsc::UnboundedSender<MissionLogAnomaly>,
+    previous: Option<String>,
+    events: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl<'a> AsyncIncrementalParser<'a> {
+    /// Start an empty parse over `lexicon`, with the receiving end of its
+    /// anomaly channel
+    pub fn new(lexicon: &'a [LexItem]) -> (Self, tokio::sync::mpsc::UnboundedReceiver<MissionLogAnomaly>) {
+        let (anomalies, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let parser = Self {
+            parser: IncrementalParser::new(lexicon),
+            anomalies,
+            previous: None,
+            events: 0,
+        };
+        (parser, receiver)
+    }
+    
+    /// Add the next token and derive as far as possible
+    ///
+    /// Fails like `IncrementalParser::feed`. An unknown token is still
+    /// reported on the anomaly channel, paired with its neighbours, before
+    /// the error is returned.
+    pub async fn feed(&mut self, token: &str) -> Result<(), DerivationError> {
+        self.parser.push(token)?;
+        self.settle(false).await
+    }
+    
+    /// Load the tokens held back as the start of a multi-word entry, like
+    /// `IncrementalParser::finish`
+    pub async fn finish(&mut self) -> Result<(), DerivationError> {
+        self.settle(true).await
+    }
+    
+    /// Check and load every settled item from the buffer, deriving after
+    /// each
+    async fn settle(&mut self, at_end: bool) -> Result<(), DerivationError> {
+        loop {
+            let item = match self.parser.next_item(at_end) {
+                Ok(Some(item)) => item,
+                Ok(None) => return Ok(()),
+                Err(e) => {
+                    if let DerivationError::UnknownToken { token, .. } = &e {
+                        self.check_event(token);
+                    }
+                    return Err(e);
+                }
+            };
+            self.check_event(&item.phon);
+            self.parser.load(item);
+            for _ in 0..DEFAULT_MAX_STEPS {
+                if !self.parser.advance()? {
+                    break;
+                }
+                tokio::task::yield_now().await;
+            }
+        }
+    }
+    
+    /// Check `event` against the event before it
+    fn check_event(&mut self, event: &str) {
+        if let Some(previous) = self.previous.replace(event.to_string()) {
+            if let Some(anomaly) = check_event_pair(self.events - 1, &previous, event, self.parser.lexicon) {
+                // Nobody listening for anomalies is not a parse failure
+                let _ = self.anomalies.send(anomaly);
+            }
+        }
+        self.events += 1;
+    }
+    
+    /// Workspace holding the analysis of the tokens fed so far
+    pub fn current_state(&self) -> &Workspace {
+        self.parser.current_state()
+    }
+}
+
+/// Parse sentence and return the operation log alongside the tree
+///
+/// Replaying the log with `apply_script` on a workspace loaded with the
+/// same tokens reproduces the tree.
+pub fn parse_traced(sentence: &str, lexicon: &[LexItem]) -> Result<(SyntacticObject, Vec<StepKind>), DerivationError> {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    check_token_count(&tokens, DEFAULT_MAX_TOKENS)?;
+    
+    let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT);
+    load_tokens(&mut workspace, &tokens, lexicon).map_err(|e| e.located_in(sentence, &tokens))?;
+    derive_traced(&mut workspace, DEFAULT_MAX_STEPS)
+}
+
+/// Parse sentence by deriving fixed-size token chunks independently, then
+/// combining the partial constituents in a final derivation.
+///
+/// Each chunk is reduced in its own `Workspace` as far as merge/move allow,
+/// so chunks share no state and can be processed in parallel by the caller.
+/// The leftover items of every chunk are then placed, in order, into a fresh
+/// workspace along with the lexicon's covert items, and derived to
+/// completion.
+///
+/// The result equals `parse_sentence` whenever every chunk boundary falls on
+/// a constituent boundary of the sequential derivation; otherwise a chunk may
+/// build a constituent the sequential parser would not, yielding a different
+/// tree or a failed parse. A `chunk_size` of 0 is treated as 1, and input
+/// over the default token limit fails with `InputTooLong`. Tokens are
+/// grouped into words as `parse_sentence` groups them, and a chunk never
+/// ends inside a multi-word entry.
+pub fn parse_chunked(sentence: &str, lexicon: &[LexItem], chunk_size: usize) -> Result<SyntacticObject, DerivationError> {
+    if lexicon.is_empty() {
+        return Err(DerivationError::EmptyLexicon);
+    }
+    
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    check_token_count(&tokens, DEFAULT_MAX_TOKENS)?;
+    let mut combined = Workspace::new(DEFAULT_MEMORY_LIMIT);
+    
+    let mut index = 0;
+    while index < tokens.len() {
+        // A multi-word entry stays whole, running past the chunk's end
+        let end = index + chunk_size.max(1);
+        let mut partial = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        while index < end.min(tokens.len()) {
+            let (lex_item, len) = match_entry(lexicon, &tokens, index)
+                .ok_or_else(|| DerivationError::unknown_token(&tokens, index).located_in(sentence, &tokens))?;
+            partial.add_lex(lex_item);
+            index += len;
+        }
+        
+        reduce(&mut partial, DEFAULT_MAX_STEPS)?;
+        combined.items.extend(partial.items);
+    }
+    combined.add_covert(lexicon);
+    
+    derive(&mut combined, DEFAULT_MAX_STEPS)
+}
+
+/// Apply derivation steps until the workspace is successful or stuck
+fn reduce(workspace: &mut Workspace, max_steps: usize) -> Result<(), DerivationError> {
+    for _ in 0..max_steps {
+        if workspace.is_successful() {
+            break;
+        }
+        
+        match step(workspace) {
+            Ok(()) => {}
+            Err(DerivationError::NoValidOperations) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    
+    Ok(())
+}
+
+/// Most parses `parse_all` returns for one sentence
+pub const MAX_PARSES: usize = 64;
+
+/// Parse sentence exploring every merge and move choice
+///
+/// Where `parse_sentence` commits to the first mergeable pair at each step,
+/// this branches on all of them and returns every structurally distinct
+/// complete derivation, up to `MAX_PARSES`. Each branch gets at most
+/// `max_steps` steps and is pruned when it exceeds the memory limit.
+/// A token with several lexical entries is tried with each of them, over at
+/// most `MAX_WEIGHTED_READINGS` combinations in lexicon order. Sentences
+/// with unknown tokens have no parses.
+///
+/// With the `rayon` feature the branches are explored in parallel; the
+/// parses returned, and their order, are the same either way.
+pub fn parse_all(sentence: &str, lexicon: &[LexItem], max_steps: usize) -> Vec<SyntacticObject> {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    if check_token_count(&tokens, DEFAULT_MAX_TOKENS).is_err() {
+        return Vec::new();
+    }
+    let Ok(readings) = lexical_readings(&tokens, lexicon, MAX_WEIGHTED_READINGS) else {
+        return Vec::new();
+    };
+    
+    let mut trees = Vec::new();
+    for reading in readings {
+        let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        for item in reading {
+            workspace.add_lex(item);
+        }
+        workspace.add_covert(lexicon);
+        
+        #[cfg(feature = "rayon")]
+        all_derivations_parallel(&workspace, max_steps, MAX_PARSES, &mut trees);
+        #[cfg(not(feature = "rayon"))]
+        all_derivations(&workspace, max_steps, MAX_PARSES, &mut trees);
+    }
+    trees
+}
+
+/// Parse sentence, choosing the derivation with the fewest steps
+///
+/// Among the trees `parse_all` finds (at most `MAX_PARSES`), returns one
+/// with the smallest `derivation_steps`, so a parse that needs no movement
+/// wins over one that does. Ties go to the tree whose `to_bracketed` form
+/// sorts first. Fails with the error `parse_sentence` reports when there is
+/// no parse at all.
+pub fn derive_minimal(sentence: &str, lexicon: &[LexItem], max_steps: usize) -> Result<SyntacticObject, DerivationError> {
+    let best = parse_all(sentence, lexicon, max_steps).into_iter()
+        .map(|tree| ((tree.derivation_steps(), tree.to_bracketed()), tree))
+        .min_by(|(a, _), (b, _)| a.cmp(b));
+    match best {
+        Some((_, tree)) => Ok(tree),
+        None => Err(parse_sentence(sentence, lexicon).err().unwrap_or(DerivationError::NoValidOperations)),
+    }
+}
+
+/// Count the structurally distinct complete derivations of a sentence
+///
+/// Derivations that differ only in the order of their operations build the
+/// same tree and count once. Unlike `parse_all` the count is not capped, and
+/// the search keeps only a canonical key per distinct structure, never the
+/// trees.
+pub fn count_derivations(sentence: &str, lexicon: &[LexItem], max_steps: usize) -> usize {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT);
+    if check_token_count(&tokens, DEFAULT_MAX_TOKENS).is_err() || load_tokens(&mut workspace, &tokens, lexicon).is_err() {
+        return 0;
+    }
+    
+    let mut keys = BTreeSet::new();
+    derivation_keys(&workspace, max_steps, &mut keys);
+    keys.len()
+}
+
+/// Most tokens, counting one per covert item of the lexicon, `recognize`
+/// accepts: the objects it builds are indexed by a bitmask over them
+pub const MAX_RECOGNIZE_TOKENS: usize = 64;
+
+/// Decide whether any derivation of the sentence succeeds
+///
+/// An exhaustive agenda search over the same merge and move operations as
+/// `derive`. The distinct objects built from each set of tokens are kept
+/// together; each new object is recorded under its token set, moved if it
+/// can be, and merged in both orders with every object built from a
+/// disjoint set. Merge does not depend on linear order, so objects are
+/// indexed by token sets rather than spans. Every reading of an ambiguous
+/// token is seeded, as is each covert item of the lexicon, and the sentence
+/// is recognized once an object covering all of them is complete.
+///
+/// The search is complete: unlike the greedy `derive` it never commits to an
+/// operation that strands other items. Identical objects are recorded once,
+/// which keeps the search small for typical grammars, but it is not
+/// polynomial: in the worst case the work grows with the number of token
+/// subsets, i.e. exponentially in the sentence length. Workspace limits such
+/// as the memory budget are not applied.
+pub fn recognize(sentence: &str, lexicon: &[LexItem]) -> bool {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    let covert: Vec<&LexItem> = lexicon.iter().filter(|item| item.is_covert()).collect();
+    let positions = tokens.len() + covert.len();
+    if tokens.is_empty() || positions > MAX_RECOGNIZE_TOKENS || check_token_count(&tokens, DEFAULT_MAX_TOKENS).is_err() {
+        return false;
+    }
+    let full = u64::MAX >> (MAX_RECOGNIZE_TOKENS - positions);
+    
+    let mut agenda = Vec::new();
+    let mut k = 0;
+    while k < tokens.len() {
+        let Some((longest, len)) = match_entry(lexicon, &tokens, k) else {
+            return false;
+        };
+        // A multi-word item covers all of its tokens
+        let mask = (u64::MAX >> (MAX_RECOGNIZE_TOKENS - len)) << k;
+        agenda.extend(lookup_all(lexicon, &longest.phon).into_iter().map(|item| (mask, SyntacticObject::from_lex(item))));
+        k += len;
+    }
+    // Each covert item gets a position of its own after the tokens, so it
+    // is used exactly once, as `load_tokens` loads it
+    for (j, item) in covert.into_iter().enumerate() {
+        agenda.push((1 << (tokens.len() + j), SyntacticObject::from_lex(item)));
+    }
+    
+    let mut built: BTreeMap<u64, Vec<SyntacticObject>> = BTreeMap::new();
+    while let Some((mask, obj)) = agenda.pop() {
+        let same_tokens = built.entry(mask).or_default();
+        if same_tokens.contains(&obj) {
+            continue;
+        }
+        same_tokens.push(obj.clone());
+        if mask == full && obj.is_saturated() {
+            return true;
+        }
+        
+        if let Ok(moved) = move_operation(obj.clone()) {
+            agenda.push((mask, moved));
+        }
+        for (&other_mask, others) in built.iter().filter(|(&m, _)| m & mask == 0) {
+            for other in others {
+                for (a, b) in [(&obj, other), (other, &obj)] {
+                    if let Ok(merged) = merge(a.clone(), b.clone()) {
+                        agenda.push((mask | other_mask, merged));
+                    }
+                }
+            }
+        }
+    }
+    
+    false
+}
+
+/// Most lexical readings `derive_weighted` considers for one sentence
+pub const MAX_WEIGHTED_READINGS: usize = 4096;
+
+/// Find the most probable derivation of a sentence under lexical weights
+///
+/// A token may match several lexical items. Each choice of one item per
+/// token is a reading, scored by the product of its items' weights; merge
+/// and move steps are deterministic and add nothing further. Readings are
+/// derived from most to least probable and the first that succeeds is
+/// returned with its score. At most `MAX_WEIGHTED_READINGS` readings are
+/// considered, and since they are enumerated best first, those are the most
+/// probable ones.
+pub fn derive_weighted(sentence: &str, lexicon: &[LexItem], max_steps: usize) -> Result<(SyntacticObject, f64), DerivationError> {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    check_token_count(&tokens, DEFAULT_MAX_TOKENS)?;
+    
+    let readings = weighted_readings(&tokens, lexicon, MAX_WEIGHTED_READINGS)
+        .map_err(|e| e.located_in(sentence, &tokens))?;
+    for (score, items) in readings {
+        let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        for item in items {
+            workspace.add_lex(item);
+        }
+        workspace.add_covert(lexicon);
+        if let Ok(tree) = derive(&mut workspace, max_steps) {
+            return Ok((tree, score));
+        }
+    }
+    
+    Err(DerivationError::NoValidOperations)
+}
+
+/// Reading on the `weighted_readings` agenda: a candidate index per token
+struct RankedReading {
+    score: f64,
+    choice: Vec<usize>,
+    /// First token whose choice successors may advance
+    from: usize,
+}
+
+impl PartialEq for RankedReading {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == core::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for RankedReading {}
+
+impl PartialOrd for RankedReading {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedReading {
+    /// Higher score first, then earlier choices in lexicon order
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.score.total_cmp(&other.score).then_with(|| other.choice.cmp(&self.choice))
+    }
+}
+
+/// The `limit` most probable lexical readings of `tokens`, best first
+///
+/// Each token's candidates are sorted by weight, so advancing any token to
+/// its next candidate never raises the score. Readings are popped from a
+/// max-heap starting from the one that takes every token's best candidate;
+/// a reading's successors advance one token at or after the last one that
+/// was advanced to reach it, which produces every reading exactly once.
+/// Equally probable readings come out in lexicon order.
+fn weighted_readings<'a>(tokens: &[&str], lexicon: &'a [LexItem], limit: usize) -> Result<Vec<(f64, Vec<&'a LexItem>)>, DerivationError> {
+    if lexicon.is_empty() {
+        return Err(DerivationError::EmptyLexicon);
+    }
+    
+    let mut candidates: Vec<Vec<&LexItem>> = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        let Some((longest, len)) = match_entry(lexicon, tokens, index) else {
+            return Err(DerivationError::unknown_token(tokens, index));
+        };
+        let mut entries = lookup_all(lexicon, &longest.phon);
+        entries.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        candidates.push(entries);
+        index += len;
+    }
+    
+    let score = |choice: &[usize]| choice.iter().zip(&candidates).map(|(&k, entries)| entries[k].weight).product::<f64>();
+    let mut agenda = BinaryHeap::new();
+    let best = vec![0; candidates.len()];
+    agenda.push(RankedReading { score: score(&best), choice: best, from: 0 });
+    
+    let mut readings = Vec::new();
+    while readings.len() < limit {
+        let Some(RankedReading { score: reading_score, choice, from }) = agenda.pop() else {
+            break;
+        };
+        for token in from..choice.len() {
+            if choice[token] + 1 < candidates[token].len() {
+                let mut next = choice.clone();
+                next[token] += 1;
+                agenda.push(RankedReading { score: score(&next), choice: next, from: token });
+            }
+        }
+        let items = choice.iter().zip(&candidates).map(|(&k, entries)| entries[k]).collect();
+        readings.push((reading_score, items));
+    }
+    
+    Ok(readings)
+}
+
+/// Find every structurally distinct parse whose linearization is the input
+///
+/// Unlike `parse_sentence`, which commits to the greedy derivation, this
+/// explores all merge and move orders, so the result is the sentence's full
+/// ambiguity set. Unknown tokens and over-long input yield no parses.
+pub fn ambiguous_parses(sentence: &str, lexicon: &[LexItem]) -> Vec<SyntacticObject> {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT);
+    if check_token_count(&tokens, DEFAULT_MAX_TOKENS).is_err() || load_tokens(&mut workspace, &tokens, lexicon).is_err() {
+        return Vec::new();
+    }
+    
+    let mut trees = Vec::new();
+    all_derivations(&workspace, DEFAULT_MAX_STEPS, usize::MAX, &mut trees);
+    
+    let surface = tokens.join(" ");
+    trees.retain(|tree| tree.linearize() == surface);
+    trees
+}
+
+/// Find the smallest workspace memory limit under which a sentence parses
+///
+/// The derivation itself does not depend on the limit, which only decides
+/// whether it is cut short, so parsing is monotone in the limit and a binary
+/// search finds the threshold. Returns `None` if the sentence does not parse
+/// even without a memory bound, or has more than the default token limit.
+pub fn min_memory_for(sentence: &str, lexicon: &[LexItem]) -> Option<usize> {
+    let tokens: Vec<&str> = sentence.split_whitespace().collect();
+    let parses = |limit| parse_tokens_with_limit(&tokens, lexicon, limit, DEFAULT_MAX_STEPS).0.is_ok();
+    
+    if check_token_count(&tokens, DEFAULT_MAX_TOKENS).is_err() || !parses(usize::MAX) {
+        return None;
+    }
+    
+    let (mut lo, mut hi) = (0, usize::MAX);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if parses(mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    Some(lo)
+}
+
+/// A log in a corpus that failed to parse
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusFailure {
+    /// Index of the log within the corpus
+    pub log_index: usize,
+    /// Index of the event blamed for the failure
+    pub position: usize,
+    /// Error the full log failed with
+    pub error: DerivationError,
+}
+
+/// Parse every log in a corpus and report the ones that fail
+///
+/// An unknown event is blamed directly. Otherwise the blamed event is the
+/// one just past the longest prefix of the log that still parses on its own
+/// (event 0 if no prefix parses).
+pub fn parse_corpus_report(logs: &[Vec<String>], lexicon: &[LexItem]) -> Vec<CorpusFailure> {
+    let mut failures = Vec::new();
+    
+    for (log_index, log) in logs.iter().enumerate() {
+        let tokens: Vec<&str> = log.iter().map(String::as_str).collect();
+        let error = match parse_tokens_debug(&tokens, lexicon).0 {
+            Ok(_) => continue,
+            Err(error) => error,
+        };
+        
+        let position = match &error {
+            DerivationError::UnknownToken { index, .. } => *index,
+            _ => (1..tokens.len())
+                .rev()
+                .find(|&k| parse_tokens_debug(&tokens[..k], lexicon).0.is_ok())
+                .unwrap_or(0),
+        };
+        
+        failures.push(CorpusFailure { log_index, position, error });
+    }
+    
+    failures
+}
+
+/// Why a pair of adjacent mission-log events was flagged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyReason {
+    /// The first event cannot combine with the second
+    UngrammaticalSequence,
+    /// At least one of the events is not in the lexicon
+    UnknownToken,
+}
+
+/// A flagged pair of adjacent events in a mission log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissionLogAnomaly {
+    /// Index of the first event of the pair
+    pub index: usize,
+    /// The first event
+    pub previous: String,
+    /// The event that follows it
+    pub current: String,
+    /// Why the pair was flagged
+    pub reason: AnomalyReason,
+    /// The error merging the pair failed with, for ungrammatical sequences
+    pub mismatch: Option<DerivationError>,
+}
+
+impl fmt::Display for MissionLogAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.reason, &self.mismatch) {
+            (AnomalyReason::UngrammaticalSequence, Some(mismatch)) => write!(
+                f,
+                "Anomaly Detected: Ungrammatical sequence '{}' followed by '{}'. This violates operational rules ({}).",
+                self.previous, self.current, mismatch
+            ),
+            (AnomalyReason::UngrammaticalSequence, None) => write!(
+                f,
+                "Anomaly Detected: Ungrammatical sequence '{}' followed by '{}'. This violates operational rules.",
+                self.previous, self.current
+            ),
+            (AnomalyReason::UnknownToken, _) => write!(
+                f,
+                "Anomaly Detected: Unknown event(s) in sequence ['{}', '{}'].",
+                self.previous, self.current
+            ),
+        }
+    }
+}
+
+/// Check every 2-event window of a log against a grammar
+///
+/// A window is flagged when either event is unknown, or when the two events
+/// cannot merge; the latter records the error `merge` reports for the pair.
+pub fn find_mission_log_anomalies(log: &[String], lexicon: &[LexItem]) -> Vec<MissionLogAnomaly> {
+    log.windows(2).enumerate()
+        .filter_map(|(index, pair)| check_event_pair(index, &pair[0], &pair[1], lexicon))
+        .collect()
+}
+
+/// Check the pair of events starting at `index` of a mission log
+fn check_event_pair(index: usize, previous: &str, current: &str, lexicon: &[LexItem]) -> Option<MissionLogAnomaly> {
+    let lookup = |event: &str| {
+        let words: Vec<&str> = event.split_whitespace().collect();
+        match_entry(lexicon, &words, 0)
+            .filter(|&(_, len)| len == words.len())
+            .map(|(item, _)| item)
+    };
+    let (reason, mismatch) = match (lookup(previous), lookup(current)) {
+        (Some(prev), Some(next)) => {
+            let (prev, next) = (SyntacticObject::from_lex(prev), SyntacticObject::from_lex(next));
+            if can_merge(&prev, &next) {
+                return None;
+            }
+            (AnomalyReason::UngrammaticalSequence, merge(prev, next).err())
+        }
+        _ => (AnomalyReason::UnknownToken, None),
+    };
+    Some(MissionLogAnomaly {
+        index,
+        previous: previous.to_string(),
+        current: current.to_string(),
+        reason,
+        mismatch,
+    })
+}
+
+/// Check that a tree's linearization parses back to an equivalent tree
+///
+/// Fails when the string does not parse at all or when the parser assigns it
+/// a different structure, exposing linearization bugs and ambiguity.
+pub fn round_trip_check(tree: &SyntacticObject, lexicon: &[LexItem]) -> bool {
+    parse_sentence(&tree.linearize(), lexicon)
+        .is_ok_and(|reparsed| reparsed.structurally_equivalent(tree))
+}
+
+/// Largest number of events `min_removal_to_valid` will try removing
+///
+/// The search tries every subset up to this size, i.e. O(n³) parses for an
+/// n-event log, which keeps it tractable for typical mission logs.
+pub const MAX_LOG_REMOVALS: usize = 3;
+
+/// Find the smallest set of event indices whose removal makes a log parse
+///
+/// Subsets are tried by increasing size and, within a size, in lexicographic
+/// order, so the first (leftmost) minimal cut is returned. An already
+/// grammatical log yields `Some(vec![])`; `None` means no cut of at most
+/// `MAX_LOG_REMOVALS` events exists.
+pub fn min_removal_to_valid(log: &[String], lexicon: &[LexItem]) -> Option<Vec<usize>> {
+    for k in 0..=MAX_LOG_REMOVALS.min(log.len()) {
+        // Lexicographic enumeration of k-subsets of 0..log.len()
+        let mut cut: Vec<usize> = (0..k).collect();
+        loop {
+            let tokens: Vec<&str> = log.iter()
+                .enumerate()
+                .filter(|(i, _)| !cut.contains(i))
+                .map(|(_, event)| event.as_str())
+                .collect();
+            if parse_tokens_debug(&tokens, lexicon).0.is_ok() {
+                return Some(cut);
+            }
+            
+            // Advance to the next subset, or move on to the next size
+            match (0..k).rev().find(|&i| cut[i] < log.len() - k + i) {
+                Some(i) => {
+                    cut[i] += 1;
+                    for j in i + 1..k {
+                        cut[j] = cut[j - 1] + 1;
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+    
+    None
+}
+
+/// Generate string of specified pattern
+///
+/// Known patterns are `"an_bn"`, `"an_bn_cn"`, `"ww"` and `"palindrome"`.
+pub fn generate_pattern(pattern: &str, n: usize) -> Result<String, DerivationError> {
+    match pattern {
+        "an_bn" => Ok(generate_an_bn(n)),
+        "an_bn_cn" => Ok(generate_an_bn_cn(n)),
+        "ww" => Ok(generate_ww(n)),
+        "palindrome" => Ok(generate_palindrome(n)),
+        _ => Err(DerivationError::InvalidOperation),
+    }
+}
+
+/// Generate strings of the specified pattern for every n in `0..=n`
+pub fn generate_pattern_upto(pattern: &str, n: usize) -> Result<Vec<String>, DerivationError> {
+    (0..=n).map(|i| generate_pattern(pattern, i)).collect()
+}
+
+/// Check if grammar can generate given string
+pub fn can_generate(pattern: &str, n: usize) -> bool {
+    generate_pattern(pattern, n).is_ok()
+}
+
+// ============================================================================
+// Python Bridge (PyO3)
+// ============================================================================
+
+#[cfg(feature = "pyo3")]
+#[pyfunction]
+/// Validates a sequence of telemetry data using a formal grammar.
+/// This is a simple version for basic anomaly detection.
+fn validate_telemetry_sequence(sequence: Vec<f64>) -> PyResult<bool> {
+    // This function is kept for backward compatibility, but the new demo
+    // should use `validate_mission_log`.
+    let is_anomalous = sequence.iter().any(|&v| v > 10.0);
+    Ok(!is_anomalous)
+}
+
+#[cfg(feature = "pyo3")]
+#[pyfunction]
+/// Validates a structured mission log against a formal grammar of operations.
+/// Returns a list of explanations for any ungrammatical (anomalous) sequences.
+fn validate_mission_log(log: Vec<String>) -> PyResult<Vec<String>> {
+    let anomalies = find_mission_log_anomalies(&log, &space_operations_lexicon());
+
+    Ok(anomalies.iter().map(ToString::to_string).collect())
+}
+
+#[cfg(feature = "pyo3")]
+#[pyfunction]
+/// Validates many mission logs in one call, returning the explanations for
+/// each log in order. The GIL is released while the logs are checked.
+fn validate_mission_logs(py: Python, logs: Vec<Vec<String>>) -> PyResult<Vec<Vec<String>>> {
+    Ok(py.allow_threads(|| {
+        let lexicon = space_operations_lexicon();
+        logs.iter()
+            .map(|log| find_mission_log_anomalies(log, &lexicon).iter().map(ToString::to_string).collect())
+            .collect()
+    }))
+}
+
+#[cfg(feature = "pyo3")]
+#[pyfunction]
+/// Checks a mission log like `validate_mission_log`, but returns each anomaly
+/// as a dict with `index`, `previous`, `current`, `reason` and `mismatch`
+/// keys, where `reason` is `"UngrammaticalSequence"` or `"UnknownToken"` and
+/// `mismatch` describes the failed feature check, or is `None`.
+fn mission_log_anomalies(py: Python, log: Vec<String>) -> PyResult<Vec<PyObject>> {
+    find_mission_log_anomalies(&log, &space_operations_lexicon()).iter().map(|anomaly| {
+        let dict = pyo3::types::PyDict::new_bound(py);
+        dict.set_item("index", anomaly.index)?;
+        dict.set_item("previous", &anomaly.previous)?;
+        dict.set_item("current", &anomaly.current)?;
+        dict.set_item("reason", format!("{:?}", anomaly.reason))?;
+        dict.set_item("mismatch", anomaly.mismatch.as_ref().map(ToString::to_string))?;
+        Ok(dict.into_any().unbind())
+    }).collect()
+}
+
+
+#[cfg(feature = "pyo3")]
+#[pyfunction]
+/// Parses a whole mission log with the grammar of space operations.
+/// Returns the derivation tree as nested dicts, or `None` if the log does not
+/// derive a single complete object.
+fn parse_mission_log(py: Python, log: Vec<String>) -> PyResult<Option<PyObject>> {
+    let tokens: Vec<&str> = log.iter().map(String::as_str).collect();
+    match parse_tokens_debug(&tokens, &space_operations_lexicon()).0 {
+        Ok(tree) => Ok(Some(tree_to_py(py, &tree)?)),
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(feature = "pyo3")]
+/// Converts a tree to a dict with `label`, `phon`, `features` and `children` keys.
+fn tree_to_py(py: Python, obj: &SyntacticObject) -> PyResult<PyObject> {
+    let dict = pyo3::types::PyDict::new_bound(py);
+    dict.set_item("label", format!("{:?}", obj.label))?;
+    dict.set_item("phon", obj.phon.as_deref())?;
+    dict.set_item("features", obj.features.iter().map(|f| format!("{:?}", f)).collect::<Vec<_>>())?;
+    let children = obj.children.iter()
+        .map(|child| tree_to_py(py, child))
+        .collect::<PyResult<Vec<_>>>()?;
+    dict.set_item("children", children)?;
+    Ok(dict.into_any().unbind())
+}
+
+#[cfg(feature = "pyo3")]
+#[pymodule]
+/// Python module for the Atomic Language Model.
+fn atomic_lang_model_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(validate_telemetry_sequence, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_mission_log, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_mission_logs, m)?)?;
+    m.add_function(wrap_pyfunction!(mission_log_anomalies, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_mission_log, m)?)?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_operations() {
+        let pos_feat = Feature::Pos(1);
+        let neg_feat = Feature::Neg(1);
+        
+        assert!(pos_feat.is_positive());
+        assert!(!pos_feat.is_negative());
+        assert!(!neg_feat.is_positive());
+        assert!(neg_feat.is_negative());
+        
+        assert_eq!(pos_feat.movement_index(), Some(1));
+        assert_eq!(neg_feat.movement_index(), Some(1));
+    }
+
+    #[test]
+    fn test_an_bn_generation() {
+        assert_eq!(generate_an_bn(0), "");
+        assert_eq!(generate_an_bn(1), "a b");
+        assert_eq!(generate_an_bn(2), "a a b b");
+        assert_eq!(generate_an_bn(3), "a a a b b b");
+    }
+
+    #[test]
+    fn test_an_bn_recognition() {
+        assert!(is_an_bn_pattern(""));
+        assert!(is_an_bn_pattern("a b"));
+        assert!(is_an_bn_pattern("a a b b"));
+        assert!(is_an_bn_pattern("a a a b b b"));
+        
+        assert!(!is_an_bn_pattern("a"));
+        assert!(!is_an_bn_pattern("a a b"));
+        assert!(!is_an_bn_pattern("a b b"));
+    }
+
+    #[test]
+    fn test_formal_language_patterns() {
+        assert_eq!(generate_pattern("an_bn_cn", 2).unwrap(), "a a b b c c");
+        assert_eq!(generate_pattern("ww", 3).unwrap(), "a b a a b a");
+        assert_eq!(generate_pattern("palindrome", 3).unwrap(), "a b a a b a");
+        assert_eq!(generate_pattern("palindrome", 2).unwrap(), "a b b a");
+        
+        for n in 0..=4 {
+            assert!(is_an_bn_cn_pattern(&generate_an_bn_cn(n)));
+            assert!(is_ww_pattern(&generate_ww(n)));
+            assert!(is_palindrome_pattern(&generate_palindrome(n)));
+        }
+        
+        assert!(!is_an_bn_cn_pattern("a a b c c"));
+        assert!(!is_an_bn_cn_pattern("a b c a b c"));
+        assert!(is_ww_pattern("b b a b b a"));
+        assert!(!is_ww_pattern("a b b a"));
+        assert!(!is_ww_pattern("c c"));
+        assert!(is_palindrome_pattern("b a a b"));
+        assert!(!is_palindrome_pattern("a b a b"));
+        assert!(!is_palindrome_pattern("a b a"));
+    }
+
+    #[test]
+    fn test_generate_pattern_upto() {
+        assert_eq!(
+            generate_pattern_upto("an_bn", 4).unwrap(),
+            vec!["", "a b", "a a b b", "a a a b b b", "a a a a b b b b"]
+        );
+        assert_eq!(generate_pattern_upto("unknown", 2), Err(DerivationError::InvalidOperation));
+    }
+
+    #[test]
+    fn test_recursive_capability() {
+        for n in 0..=5 {
+            assert!(can_generate("an_bn", n));
+        }
+    }
+
+    #[test]
+    fn test_merge_operation() {
+        let det = SyntacticObject::from_lex(&LexItem::new("the", &[Feature::Cat(Category::D)]));
+        let noun = SyntacticObject::from_lex(&LexItem::new("student", &[Feature::Cat(Category::N)]));
+        
+        // This should fail - no selector feature
+        assert!(merge(det.clone(), noun.clone()).is_err());
+        
+        // Create proper selector
+        let det_sel = SyntacticObject {
+            features: vec![Feature::Sel(Category::N)],
+            ..det
+        };
+        
+        // This should succeed
+        let merged = merge(det_sel, noun).unwrap();
+        assert_eq!(merged.label, Category::D);
+    }
+
+    #[test]
+    fn test_span_annotations() {
+        let tree = parse_sentence("CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW VOLTAGE_SPIKE", &telemetry_chain_lexicon()).unwrap();
+        
+        fn check(node: &SyntacticObject, annotated: &AnnotatedTree) {
+            assert_eq!(annotated.span, node.linearize());
+            assert_eq!(annotated.label, node.label);
+            assert_eq!(annotated.children.len(), node.children.len());
+            for (child, annotated_child) in node.children.iter().zip(&annotated.children) {
+                check(child, annotated_child);
+            }
+        }
+        
+        check(&tree, &tree.with_span_annotations());
+    }
+
+    #[test]
+    fn test_round_trip_check() {
+        let lexicon = test_lexicon();
+        let dp = parse_sentence("the student", &lexicon).unwrap();
+        assert_eq!(dp.label, Category::D);
+        assert!(round_trip_check(&dp, &lexicon));
+        
+        // Same string, but a right-branching structure the parser never builds
+        let chain = telemetry_chain_lexicon();
+        let leaf = |phon: &str| SyntacticObject::from_lex(chain.iter().find(|item| item.phon == phon).unwrap());
+        let states = SyntacticObject::internal(Category::State, vec![], vec![leaf("CURRENT_DRAW"), leaf("VOLTAGE_SPIKE")]);
+        let command = SyntacticObject::internal(Category::Command, vec![], vec![leaf("MOTOR_CMD_START"), states]);
+        let tree = SyntacticObject::internal(Category::N, vec![], vec![leaf("CTX_DRIVE"), command]);
+        assert!(!round_trip_check(&tree, &chain));
+    }
+
+    #[test]
+    fn test_workspace_operations() {
+        let mut workspace = Workspace::new(1024);
+        let lexicon = test_lexicon();
+        
+        workspace.add_lex(&lexicon[0]); // "the"
+        workspace.add_lex(&lexicon[2]); // "student"
+        
+        assert_eq!(workspace.items.len(), 2);
+        assert!(!workspace.is_successful());
+    }
+
+    /// Linear telemetry grammar whose root context carries no category, so a
+    /// fully reduced log has no unchecked features left.
+    fn telemetry_chain_lexicon() -> Vec<LexItem> {
+        vec![
+            LexItem::new("CTX_DRIVE", &[Feature::Sel(Category::Command)]),
+            LexItem::new("MOTOR_CMD_START", &[Feature::Cat(Category::Command), Feature::Sel(Category::State)]),
+            LexItem::new("CURRENT_DRAW", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
+            LexItem::new("WHEEL_RPM", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
+            LexItem::new("TEMP_MOTOR", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
+            LexItem::new("VOLTAGE_SPIKE", &[Feature::Cat(Category::State)]),
+        ]
+    }
+
+    /// Wh-question grammar in which "did" fronts "what": "saw what did"
+    /// derives "what did saw"
+    fn wh_question_lexicon() -> Vec<LexItem> {
+        vec![
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V), Feature::Pos(1)]),
+        ]
+    }
+
+    #[test]
+    fn test_parse_chunked_matches_sequential() {
+        let lexicon = telemetry_chain_lexicon();
+        let log = "CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW VOLTAGE_SPIKE";
+        
+        let sequential = parse_sentence(log, &lexicon).unwrap();
+        assert_eq!(sequential.linearize(), log);
+        
+        // Chunk boundaries at 1 and 2 tokens coincide with constituents
+        assert_eq!(parse_chunked(log, &lexicon, 1).unwrap(), sequential);
+        assert_eq!(parse_chunked(log, &lexicon, 2).unwrap(), sequential);
+        
+        assert_eq!(
+            parse_chunked("CTX_DRIVE BOGUS", &lexicon, 1),
+            Err(DerivationError::UnknownToken { token: "BOGUS".to_string(), index: 1, byte_offset: 10 })
+        );
+        
+        // Covert heads join the final derivation
+        let lexicon = wh_movement_lexicon();
+        let sequential = parse_sentence("who left", &lexicon).unwrap();
+        assert_eq!(parse_chunked("who left", &lexicon, 4).unwrap(), sequential);
+        assert_eq!(parse_chunked("who left", &lexicon, 1).unwrap(), sequential);
+    }
+
+    #[test]
+    fn test_language_infiniteness() {
+        // S → a B | a S B: recursive with a terminating base case
+        let ab = vec![
+            LexItem::new("a", &[Feature::Cat(Category::S), Feature::Sel(Category::V)]),
+            LexItem::new("a", &[Feature::Cat(Category::S), Feature::Sel(Category::S), Feature::Sel(Category::V)]),
+            LexItem::new("b", &[Feature::Cat(Category::V)]),
+        ];
+        assert!(is_language_infinite(&ab, Category::S));
+        
+        // Commands select terminal states only: finitely many logs
+        let flat = vec![
+            LexItem::new("MOTOR_CMD_START", &[Feature::Cat(Category::Command), Feature::Sel(Category::State)]),
+            LexItem::new("VOLTAGE_SPIKE", &[Feature::Cat(Category::State)]),
+        ];
+        assert!(!is_language_infinite(&flat, Category::Command));
+        
+        // A self-selecting category without a base case generates nothing
+        let unproductive = vec![
+            LexItem::new("loop", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
+        ];
+        assert!(!is_language_infinite(&unproductive, Category::State));
+    }
+
+    #[test]
+    fn test_subtree_shape_coverage() {
+        let lexicon = telemetry_chain_lexicon();
+        let trees = vec![
+            parse_sentence("CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW VOLTAGE_SPIKE", &lexicon).unwrap(),
+            parse_sentence("CTX_DRIVE MOTOR_CMD_START WHEEL_RPM VOLTAGE_SPIKE", &lexicon).unwrap(),
+        ];
+        
+        let shapes = collect_subtree_shapes(&trees);
+        assert_eq!(shapes["State"], 4);
+        assert_eq!(shapes["[State State State]"], 2);
+        assert_eq!(shapes.values().sum::<usize>(), 14);
+    }
+
+    #[test]
+    fn test_multiple_negative_features_on_mover() {
+        // "what" must check Neg(1) at the T edge and Neg(2) at the focus edge
+        let lexicon = vec![
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1), Feature::Neg(2)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V), Feature::Pos(1)]),
+            LexItem::new("focus", &[Feature::Sel(Category::S), Feature::Pos(2)]),
+        ];
+        
+        let tree = parse_sentence("saw what did focus", &lexicon).unwrap();
+        assert!(tree.is_complete());
+        assert_eq!(tree.linearize(), "what focus did saw");
+        
+        // Each landing site discharged exactly one index; the S edge copy
+        // moved on and left a trace
+        let focus_landing = &tree.children[0];
+        let s_landing = &tree.children[1].children[1].children[0];
+        assert_eq!(focus_landing.features, vec![Feature::Cat(Category::D)]);
+        assert_eq!(s_landing.trace, Some(2));
+    }
+
+    #[test]
+    fn test_feature_summary() {
+        // Three merges and two movements build "what focus did saw"
+        let lexicon = vec![
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1), Feature::Neg(2)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V), Feature::Pos(1)]),
+            LexItem::new("focus", &[Feature::Sel(Category::S), Feature::Pos(2)]),
+        ];
+        let tree = parse_sentence("saw what did focus", &lexicon).unwrap();
+        assert_eq!(tree.feature_summary(), FeatureSummary {
+            selectors: 3,
+            movements: 2,
+            contexts: 0,
+            agreements: 0,
+        });
+        
+        // A context on a child counts once the node no longer carries it
+        let motor = SyntacticObject::from_lex(&LexItem::new("MOTOR_CMD_START", &[
+            Feature::Cat(Category::Command),
+            Feature::Sel(Category::State),
+            Feature::Ctx("DRIVE".to_string()),
+        ]));
+        let spike = SyntacticObject::from_lex(&LexItem::new("VOLTAGE_SPIKE", &[Feature::Cat(Category::State)]));
+        let tree = merge(motor, spike).unwrap();
+        assert_eq!(tree.feature_summary().contexts, 0);
+        let checked = SyntacticObject::internal(tree.label.clone(), vec![Feature::Cat(Category::Command)], tree.children);
+        assert_eq!(checked.feature_summary(), FeatureSummary {
+            selectors: 1,
+            movements: 0,
+            contexts: 1,
+            agreements: 0,
+        });
+    }
+
+    #[test]
+    fn test_movement_reachability() {
+        // "said" triggers +1 but no item in the test lexicon carries -1
+        let warnings = check_movement_reachability(&test_lexicon());
+        assert_eq!(warnings, vec![MovementWarning { phon: "said".to_string(), index: 1 }]);
+        
+        // -1 only on something "did" cannot dominate
+        let mut broken = vec![
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V), Feature::Pos(1)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("it", &[Feature::Cat(Category::D)]),
+            LexItem::new("why", &[Feature::Cat(Category::C), Feature::Neg(1)]),
+        ];
+        assert_eq!(check_movement_reachability(&broken).len(), 1);
+        
+        broken.push(LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1)]));
+        assert!(check_movement_reachability(&broken).is_empty());
+    }
+
+    #[test]
+    fn test_check_movement_features() {
+        let warnings = Grammar::new(test_lexicon(), Category::V).check_movement_features();
+        assert_eq!(warnings, vec![FeatureWarning { feature: Feature::Pos(1), carriers: vec!["said".to_string()] }]);
+        assert_eq!(warnings[0].to_string(), "+1 on 'said' has no -1 anywhere in the lexicon");
+        
+        // Movers with no trigger are reported the other way round
+        let lexicon = vec![
+            LexItem::new("who", &[Feature::Cat(Category::D), Feature::Neg(2)]),
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(2)]),
+            LexItem::new("left", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+        ];
+        assert_eq!(Grammar::new(lexicon, Category::V).check_movement_features(), vec![FeatureWarning {
+            feature: Feature::Neg(2),
+            carriers: vec!["who".to_string(), "what".to_string()],
+        }]);
+        
+        assert!(Grammar::new(wh_movement_lexicon(), Category::C).check_movement_features().is_empty());
+    }
+
+    #[test]
+    fn test_check_termination() {
+        // States select states, but VOLTAGE_SPIKE ends the chain
+        let telemetry = Grammar::new(space_operations_lexicon(), Category::Context);
+        let warnings = telemetry.check_termination();
+        assert_eq!(warnings, vec![TerminationWarning {
+            cycle: vec![Category::State],
+            kind: RecursionKind::Productive,
+        }]);
+        assert_eq!(warnings[0].to_string(), "selection cycle State -> State is productive recursion with a base case");
+        
+        // Without it, every state needs another state
+        let mut lexicon = space_operations_lexicon();
+        lexicon.retain(|item| item.phon != "VOLTAGE_SPIKE");
+        let warnings = Grammar::new(lexicon, Category::Context).check_termination();
+        assert_eq!(warnings[0].kind, RecursionKind::Unbounded);
+        
+        // A longer cycle is reported once, from its first head in the lexicon
+        let lexicon = vec![
+            LexItem::new("that", &[Feature::Cat(Category::C), Feature::Sel(Category::V)]),
+            LexItem::new("said", &[Feature::Cat(Category::V), Feature::Sel(Category::C)]),
+            LexItem::new("left", &[Feature::Cat(Category::V)]),
+        ];
+        let warnings = Grammar::new(lexicon, Category::V).check_termination();
+        assert_eq!(warnings, vec![TerminationWarning {
+            cycle: vec![Category::C, Category::V],
+            kind: RecursionKind::Productive,
+        }]);
+        assert!(Grammar::new(test_lexicon(), Category::V).check_termination().iter().all(|w| w.kind == RecursionKind::Productive));
+    }
+
+    #[test]
+    fn test_argument_structure() {
+        let lexicon = vec![
+            LexItem::new("saw", &[Feature::Sel(Category::D)]),
+            LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+            LexItem::new("student", &[Feature::Cat(Category::N)]),
+        ];
+        
+        // Tokens are ordered so the DP is built before the verb selects it
+        let tree = parse_sentence("the student saw", &lexicon).unwrap();
+        assert_eq!(tree.linearize(), "saw the student");
+        assert_eq!(tree.argument_structure(), vec![
+            ("saw".to_string(), vec!["the student".to_string()]),
+            ("the".to_string(), vec!["student".to_string()]),
+        ]);
+        
+        // A selector percolated from a selectee is credited to its owner
+        let chain = parse_sentence("CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW VOLTAGE_SPIKE", &telemetry_chain_lexicon()).unwrap();
+        let grid = chain.argument_structure();
+        assert!(grid.contains(&("CTX_DRIVE".to_string(), vec!["MOTOR_CMD_START".to_string()])));
+        assert!(grid.contains(&("MOTOR_CMD_START".to_string(), vec!["CURRENT_DRAW VOLTAGE_SPIKE".to_string()])));
+        assert!(grid.contains(&("CURRENT_DRAW".to_string(), vec!["VOLTAGE_SPIKE".to_string()])));
+    }
+
+    #[test]
+    fn test_parse_sentence_debug_keeps_workspace() {
+        let lexicon = telemetry_chain_lexicon();
+        
+        let (result, workspace) = parse_sentence_debug("CTX_DRIVE MOTOR_CMD_START VOLTAGE_SPIKE", &lexicon);
+        assert_eq!(result.unwrap(), workspace.items[0]);
+        assert_eq!(workspace.step_count, 2);
+        
+        // The trailing selector is stranded with nothing to select
+        let (result, workspace) = parse_sentence_debug("CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW", &lexicon);
+        assert_eq!(result, Err(DerivationError::NoValidOperations));
+        assert_eq!(workspace.items.len(), 1);
+        assert_eq!(workspace.items[0].features, vec![Feature::Sel(Category::State)]);
+    }
+
+    #[test]
+    fn test_arity_mismatch() {
+        let lexicon = telemetry_chain_lexicon();
+        let log = "CTX_DRIVE MOTOR_CMD_START VOLTAGE_SPIKE";
+        
+        let mut arity = BTreeMap::new();
+        arity.insert(Category::Command, 1);
+        arity.insert(Category::State, 0);
+        assert!(parse_with_arity(log, &lexicon, &arity).is_ok());
+        
+        // Commands declared binary, but the lexicon only gives them one selector
+        arity.insert(Category::Command, 2);
+        assert_eq!(
+            parse_with_arity(log, &lexicon, &arity),
+            Err(DerivationError::ArityMismatch { category: Category::Command, expected: 2, actual: 1 })
+        );
+        
+        // The merge that would complete the command is never made
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT).with_arity(Category::Command, 2);
+        ws.add_lex(&lexicon[1]);
+        ws.add_lex(&lexicon[5]);
+        assert!(find_mergeable_pairs(&ws).is_empty());
+        assert_eq!(step(&mut ws), Err(DerivationError::ArityMismatch { category: Category::Command, expected: 2, actual: 1 }));
+        assert_eq!(ws.items.len(), 2);
+    }
+
+    #[test]
+    fn test_tokenizer_delimiters() {
+        let tokenizer = Tokenizer::with_delimiters(&[',', ';']);
+        assert_eq!(tokenizer.tokenize("MOTOR_CMD_START,VOLTAGE_SPIKE"), vec!["MOTOR_CMD_START", "VOLTAGE_SPIKE"]);
+        assert_eq!(tokenizer.tokenize(" A, B;;C\tD ,"), vec!["A", "B", "C", "D"]);
+        assert_eq!(Tokenizer::default().tokenize("A,B C"), vec!["A,B", "C"]);
+        
+        let lexicon = telemetry_chain_lexicon();
+        let expected = parse_sentence("CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW VOLTAGE_SPIKE", &lexicon).unwrap();
+        assert_eq!(parse_with_tokenizer("CTX_DRIVE,MOTOR_CMD_START,CURRENT_DRAW,VOLTAGE_SPIKE", &lexicon, &tokenizer).unwrap(), expected);
+        assert_eq!(parse_with_tokenizer("CTX_DRIVE; MOTOR_CMD_START, CURRENT_DRAW VOLTAGE_SPIKE", &lexicon, &tokenizer).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_min_removal_to_valid() {
+        let lexicon = telemetry_chain_lexicon();
+        let log = |events: &[&str]| events.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        
+        let valid = log(&["CTX_DRIVE", "MOTOR_CMD_START", "VOLTAGE_SPIKE"]);
+        assert_eq!(min_removal_to_valid(&valid, &lexicon), Some(vec![]));
+        
+        // A second spike has nothing to select it
+        let extra = log(&["CTX_DRIVE", "MOTOR_CMD_START", "VOLTAGE_SPIKE", "VOLTAGE_SPIKE"]);
+        assert_eq!(min_removal_to_valid(&extra, &lexicon), Some(vec![2]));
+        
+        let unknown = log(&["CTX_DRIVE", "GLITCH", "MOTOR_CMD_START", "VOLTAGE_SPIKE"]);
+        assert_eq!(min_removal_to_valid(&unknown, &lexicon), Some(vec![1]));
+        
+        // Four unknown events exceed the removal bound
+        let noise = log(&["X", "X", "X", "X", "CTX_DRIVE", "MOTOR_CMD_START", "VOLTAGE_SPIKE"]);
+        assert_eq!(min_removal_to_valid(&noise, &lexicon), None);
+    }
+
+    #[test]
+    fn test_optional_features() {
+        // Optional selector: the command may or may not report a state
+        let lexicon = vec![
+            LexItem::new("CTX_DRIVE", &[Feature::Sel(Category::Command)]),
+            LexItem::new("MOTOR_CMD_START", &[
+                Feature::Cat(Category::Command),
+                Feature::Optional(Box::new(Feature::Sel(Category::State))),
+            ]),
+            LexItem::new("VOLTAGE_SPIKE", &[Feature::Cat(Category::State)]),
+        ];
+        assert!(parse_sentence("CTX_DRIVE MOTOR_CMD_START", &lexicon).is_ok());
+        let full = parse_sentence("CTX_DRIVE MOTOR_CMD_START VOLTAGE_SPIKE", &lexicon).unwrap();
+        assert_eq!(full.linearize(), "CTX_DRIVE MOTOR_CMD_START VOLTAGE_SPIKE");
+        
+        // Optional movement: fronts a wh-phrase if there is one
+        let lexicon = vec![
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1)]),
+            LexItem::new("it", &[Feature::Cat(Category::D)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("did", &[
+                Feature::Cat(Category::S),
+                Feature::Sel(Category::V),
+                Feature::Optional(Box::new(Feature::Pos(1))),
+            ]),
+        ];
+        let moved = parse_sentence("saw what did", &lexicon).unwrap();
+        assert_eq!(moved.linearize(), "what did saw");
+        assert_eq!(moved.features, vec![Feature::Cat(Category::S)]);
+        
+        let in_situ = parse_sentence("saw it did", &lexicon).unwrap();
+        assert_eq!(in_situ.linearize(), "did saw it");
+        assert!(in_situ.is_saturated());
+        
+        // The lexicon analyses look inside optional movement features
+        let mut lexicon = lexicon;
+        lexicon[0] = LexItem::new("what", &[Feature::Cat(Category::D), Feature::Optional(Box::new(Feature::Neg(1)))]);
+        assert!(parse_sentence("saw what did", &lexicon).is_ok());
+        assert_eq!(check_movement_reachability(&lexicon), vec![]);
+        assert_eq!(Grammar::new(lexicon.clone(), Category::S).check_movement_features(), vec![]);
+        lexicon[3].feats.push(Feature::Optional(Box::new(Feature::Pos(2))));
+        assert_eq!(check_movement_reachability(&lexicon), vec![MovementWarning { phon: "did".to_string(), index: 2 }]);
+        assert_eq!(Grammar::new(lexicon, Category::S).check_movement_features().len(), 1);
+    }
+
+    #[test]
+    fn test_lexicalize_dp_skeleton() {
+        let lexicon = test_lexicon();
+        let leaf = |cat| SyntacticObject::internal(cat, vec![], vec![]);
+        let skeleton = SyntacticObject::internal(
+            Category::D,
+            vec![],
+            vec![leaf(Category::D), leaf(Category::N)],
+        );
+        
+        let all = lexicalize(&skeleton, &lexicon, 100);
+        let strings: Vec<String> = all.iter().map(|t| t.linearize()).collect();
+        assert_eq!(strings, vec![
+            "the student", "the tutor", "the teacher",
+            "a student", "a tutor", "a teacher",
+        ]);
+        assert!(all.iter().all(|t| t.is_saturated()));
+        
+        assert_eq!(lexicalize(&skeleton, &lexicon, 2).len(), 2);
+        
+        // No lexical item of category S, so nothing fills the skeleton
+        let skeleton = SyntacticObject::internal(
+            Category::D,
+            vec![],
+            vec![leaf(Category::D), leaf(Category::S)],
+        );
+        assert!(lexicalize(&skeleton, &lexicon, 100).is_empty());
+    }
+
+    #[test]
+    fn test_find_failure_step() {
+        let lexicon = vec![
+            LexItem::new("c1", &[Feature::Cat(Category::C), Feature::Sel(Category::D)]),
+            LexItem::new("d1", &[Feature::Cat(Category::D)]),
+            LexItem::new("d2", &[Feature::Cat(Category::D), Feature::Sel(Category::C)]),
+        ];
+        let workspace = |sentence: &str| {
+            let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+            for word in sentence.split_whitespace() {
+                ws.add_lex(lexicon.iter().find(|item| item.phon == word).unwrap());
+            }
+            ws
+        };
+        
+        assert_eq!(find_failure_step(&workspace("c1 d1 d2"), DEFAULT_MAX_STEPS), None);
+        
+        // Greedily merging c1 with d2 first strands d1, although
+        // building [c1 d1] first would still have succeeded
+        let ws = workspace("c1 d2 d1");
+        assert!(parse_sentence("c1 d2 d1", &lexicon).is_err());
+        assert!(is_recoverable(&ws, DEFAULT_MAX_STEPS, &mut BTreeMap::new()));
+        assert_eq!(find_failure_step(&ws, DEFAULT_MAX_STEPS), Some(1));
+        
+        // Only one d1 can ever be selected, so the derivation is doomed from the start
+        assert_eq!(find_failure_step(&workspace("c1 d1 d1"), DEFAULT_MAX_STEPS), Some(0));
+        
+        // Eight stranded DPs can be built in 8! orders from 64 first merges,
+        // but the search expands each multiset of items once
+        let lexicon = test_lexicon();
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        for _ in 0..8 {
+            ws.add_lex(&lexicon[0]);
+            ws.add_lex(&lexicon[2]);
+        }
+        let mut dead_ends = BTreeMap::new();
+        assert!(!is_recoverable(&ws, DEFAULT_MAX_STEPS, &mut dead_ends));
+        assert_eq!(dead_ends.len(), 9);
+    }
+
+    #[test]
+    fn test_to_ptb() {
+        let lexicon = test_lexicon();
+        let dp = parse_sentence("the student", &lexicon).unwrap();
+        assert_eq!(dp.to_ptb(), "(D (D the) (N student))");
+        
+        let leaf = SyntacticObject::from_lex(&LexItem::new("(x)", &[Feature::Cat(Category::N)]));
+        assert_eq!(leaf.to_ptb(), "(N -LRB-x-RRB-)");
+    }
+
+    #[test]
+    fn test_embedding_limit() {
+        let that = SyntacticObject::from_lex(&LexItem::new("that", &[Feature::Cat(Category::C), Feature::Sel(Category::S)]));
+        let thinks = SyntacticObject::from_lex(&LexItem::new("thinks", &[Feature::Cat(Category::S), Feature::Sel(Category::C)]));
+        let left = SyntacticObject::from_lex(&LexItem::new("left", &[Feature::Cat(Category::S)]));
+        
+        // [thinks [that ... [thinks [that left]]]] with `depth` CPs
+        let clause = |depth: usize| {
+            let mut s = left.clone();
+            for _ in 0..depth {
+                let cp = merge(that.clone(), s).unwrap();
+                s = merge(thinks.clone(), cp).unwrap();
+            }
+            s
+        };
+        let workspace = |depth: usize| {
+            let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT).with_embedding_limit(Category::C, 2);
+            ws.items = vec![that.clone(), clause(depth)];
+            ws
+        };
+        
+        let mut ws = workspace(1);
+        assert_eq!(step(&mut ws), Ok(()));
+        assert_eq!(ws.items[0].embedding_depth(&Category::C), 2);
+        
+        // The over-limit merge is not committed
+        let mut ws = workspace(2);
+        let before = ws.items.clone();
+        assert_eq!(step(&mut ws), Err(DerivationError::EmbeddingLimitExceeded(Category::C)));
+        assert_eq!(ws.items, before);
+        assert_eq!(ws.merge_successes(), 0);
+    }
+
+    #[test]
+    fn test_ambiguous_parses() {
+        // "there" can attach to the verb phrase or to the noun
+        let lexicon = vec![
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::N)]),
+            LexItem::new("spies", &[Feature::Cat(Category::N), Feature::Sel(Category::D)]),
+            LexItem::new("there", &[Feature::Cat(Category::D)]),
+        ];
+        
+        let parses = ambiguous_parses("saw spies there", &lexicon);
+        let mut shapes: Vec<String> = parses.iter().map(|t| t.shape()).collect();
+        shapes.sort();
+        assert_eq!(shapes, vec!["[V V [N N D]]", "[V [V V N] D]"]);
+        assert!(parses.iter().all(|t| t.linearize() == "saw spies there"));
+        
+        assert!(ambiguous_parses("saw unicorns", &lexicon).is_empty());
+    }
+
+    #[test]
+    fn test_rename_movement_indices() {
+        let what = SyntacticObject::from_lex(&LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1)]));
+        let saw = SyntacticObject::from_lex(&LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]));
+        let did = SyntacticObject::from_lex(&LexItem::new("did", &[
+            Feature::Cat(Category::S),
+            Feature::Sel(Category::V),
+            Feature::Pos(1),
+        ]));
+        let vp = merge(saw, what).unwrap();
+        
+        // Both constituents use index 1, so the trigger finds the wh-phrase
+        let clash = merge(did.clone(), vp.clone()).unwrap();
+        assert!(move_operation(clash).is_ok());
+        
+        // Shifted apart, the two index-1 features no longer interact
+        let renamed = vp.rename_movement_indices(1);
+        assert_eq!(renamed.features, vec![Feature::Cat(Category::V), Feature::Neg(2)]);
+        assert_eq!(renamed.children[1].features, vec![Feature::Cat(Category::D), Feature::Neg(2)]);
+        let apart = merge(did, renamed).unwrap();
+        assert_eq!(move_operation(apart), Err(DerivationError::NoValidOperations));
+    }
+
+    #[test]
+    fn test_empty_lexicon() {
+        assert_eq!(parse_sentence("the student", &[]), Err(DerivationError::EmptyLexicon));
+        assert_eq!(parse_chunked("the student", &[], 1), Err(DerivationError::EmptyLexicon));
+    }
+
+    #[test]
+    fn test_distinguishing_examples() {
+        let lex_a = vec![
+            LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+            LexItem::new("student", &[Feature::Cat(Category::N)]),
+        ];
+        let mut lex_b = lex_a.clone();
+        lex_b.push(LexItem::new("a", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]));
+        
+        assert!(distinguishing_examples(&lex_a, &lex_a, Category::D, 3).is_empty());
+        assert_eq!(distinguishing_examples(&lex_a, &lex_b, Category::D, 2), vec!["a student"]);
+        assert_eq!(distinguishing_examples(&lex_b, &lex_a, Category::D, 2), vec!["a student"]);
+    }
+
+    #[test]
+    fn test_to_lex_item() {
+        let mut lexicon = test_lexicon();
+        lexicon.push(LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]));
+        let dp = parse_sentence("the student", &lexicon).unwrap();
+        
+        let frozen = dp.to_lex_item("the_student");
+        assert_eq!(frozen, LexItem::new("the_student", &[Feature::Cat(Category::D)]));
+        
+        let saw = SyntacticObject::from_lex(lexicon.last().unwrap());
+        let with_phrase = merge(saw.clone(), dp).unwrap();
+        let with_frozen = merge(saw, SyntacticObject::from_lex(&frozen)).unwrap();
+        assert_eq!(with_frozen.features, with_phrase.features);
+        
+        lexicon.push(frozen);
+        assert_eq!(parse_sentence("saw the_student", &lexicon).unwrap().linearize(), "saw the_student");
+    }
+
+    #[test]
+    fn test_success_invariant_under_shuffled_pairs() {
+        let lexicon = vec![
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::N)]),
+            LexItem::new("spies", &[Feature::Cat(Category::N), Feature::Sel(Category::D)]),
+            LexItem::new("there", &[Feature::Cat(Category::D)]),
+        ];
+        
+        let mut shapes = Vec::new();
+        for seed in 0..64 {
+            let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT).with_shuffle_seed(seed);
+            for item in &lexicon {
+                ws.add_lex(item);
+            }
+            let tree = derive(&mut ws, DEFAULT_MAX_STEPS).unwrap();
+            assert_eq!(tree.linearize(), "saw spies there");
+            if !shapes.contains(&tree.shape()) {
+                shapes.push(tree.shape());
+            }
+        }
+        
+        // Both attachments are reached, so the order really did vary
+        assert_eq!(shapes.len(), 2);
+    }
+
+    #[test]
+    fn test_complement_and_specifier() {
+        // "john" raises from inside the NP complement to Spec,DP
+        let lexicon = vec![
+            LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N), Feature::Pos(1)]),
+            LexItem::new("student", &[Feature::Cat(Category::N), Feature::Sel(Category::D)]),
+            LexItem::new("john", &[Feature::Cat(Category::D), Feature::Neg(1)]),
+        ];
+        let dp = parse_sentence("student john the", &lexicon).unwrap();
+        assert_eq!(dp.label, Category::D);
+        
+        assert_eq!(dp.lexical_head().phon.as_deref(), Some("the"));
+        assert_eq!(dp.complement().unwrap().linearize(), "student");
+        assert_eq!(dp.specifier().unwrap().phon.as_deref(), Some("john"));
+        
+        // A bare head-complement phrase has no specifier
+        let inner = &dp.children[1];
+        assert_eq!(inner.complement().unwrap().linearize(), "student");
+        assert!(inner.specifier().is_none());
+        assert!(inner.children[0].complement().is_none());
+    }
+
+    #[test]
+    fn test_engine_derives_an_bn() {
+        // The engine itself, not string concatenation, builds aⁿbⁿ
+        for n in 0..=6 {
+            let tree = derive_an_bn(n).unwrap();
+            assert!(tree.is_saturated());
+            assert_eq!(tree.label, Category::S);
+            assert_eq!(tree.linearize(), generate_an_bn(n));
+            assert_eq!(tree.movement_chains().len(), n);
+        }
+        
+        // The step budget grows with n rather than capping it
+        assert_eq!(derive_an_bn(40).unwrap().linearize(), generate_an_bn(40));
+    }
+
+    #[test]
+    fn test_parse_sentence_with_limits() {
+        // Each "h" and the "t" above them attract their complement five
+        // times, so most merges are followed by five movement steps
+        let with_movers = |mut features: Vec<Feature>, n| {
+            features.extend(vec![Feature::Neg(1); n]);
+            features
+        };
+        let head = |cat, movers| {
+            let mut features = vec![Feature::Sel(Category::D)];
+            features.extend(vec![Feature::Pos(1); 5]);
+            features.push(Feature::Cat(cat));
+            with_movers(features, movers)
+        };
+        let lexicon = vec![
+            LexItem::new("c", &[Feature::Sel(Category::V), Feature::Cat(Category::C)]),
+            LexItem::new("t", &head(Category::V, 0)),
+            LexItem::new("h", &head(Category::D, 5)),
+            LexItem::new("x", &with_movers(vec![Feature::Cat(Category::D)], 5)),
+        ];
+        let sentence = format!("x {} t c", ["h"; 17].join(" "));
+        assert_eq!(sentence.split_whitespace().count(), 20);
+        
+        // 19 merges and 90 moves overrun the default budget of 100 steps
+        assert_eq!(parse_sentence(&sentence, &lexicon), Err(DerivationError::NoValidOperations));
+        assert!(parse_sentence_with_limits(&sentence, &lexicon, DEFAULT_MEMORY_LIMIT, 108).is_err());
+        let tree = parse_sentence_with_limits(&sentence, &lexicon, DEFAULT_MEMORY_LIMIT, 109).unwrap();
+        assert_eq!(tree.label, Category::C);
+        
+        assert_eq!(
+            parse_sentence_with_limits(&sentence, &lexicon, 64, 109),
+            Err(DerivationError::MemoryLimitExceeded),
+        );
+    }
+
+    #[test]
+    fn test_min_memory_for() {
+        let lexicon = telemetry_chain_lexicon();
+        let sentence = "CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW VOLTAGE_SPIKE";
+        let tokens: Vec<&str> = sentence.split_whitespace().collect();
+        
+        let limit = min_memory_for(sentence, &lexicon).unwrap();
+        assert!(parse_tokens_with_limit(&tokens, &lexicon, limit, DEFAULT_MAX_STEPS).0.is_ok());
+        assert_eq!(
+            parse_tokens_with_limit(&tokens, &lexicon, limit - 1, DEFAULT_MAX_STEPS).0,
+            Err(DerivationError::MemoryLimitExceeded),
+        );
+        
+        assert_eq!(min_memory_for("VOLTAGE_SPIKE CURRENT_DRAW CTX_DRIVE", &lexicon), None);
+    }
+
+    #[test]
+    fn test_parse_corpus_report() {
+        let lexicon = telemetry_chain_lexicon();
+        let log = |events: &[&str]| events.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        let logs = vec![
+            log(&["CTX_DRIVE", "MOTOR_CMD_START", "CURRENT_DRAW", "VOLTAGE_SPIKE"]),
+            log(&["CTX_DRIVE", "MOTOR_CMD_START", "VOLTAGE_SPIKE", "CTX_DRIVE"]),
+            log(&["CTX_DRIVE", "MOTOR_CMD_START", "WARP_DRIVE"]),
+        ];
+        
+        assert_eq!(parse_corpus_report(&logs, &lexicon), vec![
+            CorpusFailure { log_index: 1, position: 3, error: DerivationError::NoValidOperations },
+            CorpusFailure {
+                log_index: 2,
+                position: 2,
+                error: DerivationError::UnknownToken { token: "WARP_DRIVE".to_string(), index: 2, byte_offset: 26 },
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_symmetric_merge() {
+        let temp = |symmetric: bool| {
+            let mut feats = vec![Feature::Cat(Category::State), Feature::Sel(Category::State)];
+            if symmetric {
+                feats.push(Feature::Symmetric);
+            }
+            SyntacticObject::from_lex(&LexItem::new("TEMP_MOTOR", &feats))
+        };
+        let draw = SyntacticObject::from_lex(&LexItem::new("CURRENT_DRAW", &[Feature::Cat(Category::State)]));
+        
+        // CURRENT_DRAW has no selector at all, so none is reported
+        let err = merge(draw.clone(), temp(false)).unwrap_err();
+        assert_eq!(err, DerivationError::FeatureMismatch {
+            expected: None,
+            found: Some(Feature::Cat(Category::State)),
+        });
+        assert_eq!(err.to_string(), "Feature mismatch: expected a selector, found Cat(State)");
+        assert!(!can_merge(&draw, &temp(false)));
+        
+        let forward = merge(temp(true), draw.clone()).unwrap();
+        let backward = merge(draw.clone(), temp(true)).unwrap();
+        assert!(can_merge(&draw, &temp(true)));
+        assert!(forward.structurally_equivalent(&backward));
+        assert_eq!(backward.linearize(), "TEMP_MOTOR CURRENT_DRAW");
+        assert!(backward.is_saturated());
+    }
+
+    #[test]
+    fn test_operation_counters() {
+        let lexicon = wh_question_lexicon();
+        let (result, ws) = parse_sentence_debug("saw what did", &lexicon);
+        assert!(result.is_ok());
+        assert_eq!((ws.merge_attempts(), ws.merge_successes()), (2, 2));
+        assert_eq!((ws.move_attempts(), ws.move_successes()), (1, 1));
+        
+        // Stuck with two items: each is tried for movement, and both fail
+        let (result, ws) = parse_sentence_debug("the student left", &test_lexicon());
+        assert!(result.is_err());
+        assert_eq!((ws.merge_attempts(), ws.merge_successes()), (1, 1));
+        assert_eq!((ws.move_attempts(), ws.move_successes()), (2, 0));
+    }
+
+    #[test]
+    fn test_to_rows() {
+        let tree = parse_sentence("CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW VOLTAGE_SPIKE", &telemetry_chain_lexicon()).unwrap();
+        let rows = tree.to_rows();
+        
+        fn count(obj: &SyntacticObject) -> usize {
+            1 + obj.children.iter().map(count).sum::<usize>()
+        }
+        assert_eq!(rows.len(), count(&tree));
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[0], (0, tree.label.clone(), None));
+        assert_eq!(rows[2], (2, Category::N, Some("CTX_DRIVE".to_string())));
+        assert_eq!(rows[6], (2, Category::State, Some("VOLTAGE_SPIKE".to_string())));
+    }
+
+    #[test]
+    fn test_max_tokens_guard() {
+        let lexicon = test_lexicon();
+        assert!(parse_with_max_tokens("the student", &lexicon, 2).is_ok());
+        assert_eq!(
+            parse_with_max_tokens("the student", &lexicon, 1),
+            Err(DerivationError::InputTooLong { tokens: 2, limit: 1 }),
+        );
+        
+        // Refused before any token is looked up
+        let huge = vec!["nonsense"; DEFAULT_MAX_TOKENS + 1].join(" ");
+        assert_eq!(
+            parse_sentence(&huge, &lexicon),
+            Err(DerivationError::InputTooLong { tokens: DEFAULT_MAX_TOKENS + 1, limit: DEFAULT_MAX_TOKENS }),
+        );
+        
+        // Every entry point that takes a sentence applies the same guard
+        assert_eq!(
+            parse_chunked(&huge, &lexicon, 4),
+            Err(DerivationError::InputTooLong { tokens: DEFAULT_MAX_TOKENS + 1, limit: DEFAULT_MAX_TOKENS }),
+        );
+        let long = vec!["the student"; DEFAULT_MAX_TOKENS / 2 + 1].join(" ");
+        assert!(ambiguous_parses(&long, &lexicon).is_empty());
+        assert_eq!(min_memory_for(&long, &lexicon), None);
+        assert_eq!(count_derivations(&long, &lexicon, DEFAULT_MAX_STEPS), 0);
+        assert!(!recognize(&long, &lexicon));
+    }
+
+    #[test]
+    fn test_active_contexts() {
+        let motor = SyntacticObject::from_lex(&LexItem::new("MOTOR_CMD_START", &[
+            Feature::Cat(Category::Command),
+            Feature::Sel(Category::State),
+            Feature::Ctx("DRIVE".to_string()),
+        ]));
+        let spike = SyntacticObject::from_lex(&LexItem::new("VOLTAGE_SPIKE", &[
+            Feature::Cat(Category::State),
+            Feature::Ctx("STANDBY".to_string()),
+            Feature::Ctx("DRIVE".to_string()),
+        ]));
+        
+        // Unchecked contexts percolate to the root
+        let tree = merge(motor, spike).unwrap();
+        assert_eq!(tree.active_contexts(false), vec!["DRIVE", "STANDBY"]);
+        
+        // Once checked at the root, they are only found by searching the tree
+        let checked = SyntacticObject::internal(tree.label.clone(), vec![Feature::Cat(Category::Command)], tree.children);
+        assert!(checked.active_contexts(false).is_empty());
+        assert_eq!(checked.active_contexts(true), vec!["DRIVE", "STANDBY"]);
+    }
+
+    #[test]
+    fn test_first_terminals() {
+        // Over-approximated: the covert center and "b" head S phrases too,
+        // although only the fronted "a" phrase ever comes first
+        let first = first_terminals(&an_bn_lexicon(), Category::S);
+        let expected: BTreeSet<String> = ["", "a", "b"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(first, expected);
+        
+        // Wh-movement lets a clause begin with the fronted phrase
+        let lexicon = wh_question_lexicon();
+        let expected: BTreeSet<String> = ["did", "what"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(first_terminals(&lexicon, Category::S), expected);
+        assert!(first_terminals(&lexicon, Category::C).is_empty());
+    }
+
+    #[test]
+    fn test_merge_provenance() {
+        let lexicon = test_lexicon();
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT).with_provenance();
+        ws.add_lex(&lexicon[0]);
+        ws.add_lex(&lexicon[2]);
+        let dp = derive(&mut ws, DEFAULT_MAX_STEPS).unwrap();
+        
+        let (selector, selectee) = &**dp.provenance.as_ref().unwrap();
+        assert_eq!(selector, &vec![Feature::Cat(Category::D), Feature::Sel(Category::N)]);
+        assert_eq!(selectee, &vec![Feature::Cat(Category::N)]);
+        assert_eq!(dp.features, vec![Feature::Cat(Category::D)]);
+        
+        // Off by default
+        assert!(parse_sentence("the student", &lexicon).unwrap().provenance.is_none());
+    }
+
+    #[test]
+    fn test_can_be_adjacent() {
+        let lexicon = wh_question_lexicon();
+        let obj = |phon: &str| SyntacticObject::from_lex(lexicon.iter().find(|item| item.phon == phon).unwrap());
+        
+        // Neither selects the other, but fronting "what" puts it before "did"
+        assert!(!can_merge(&obj("what"), &obj("did")) && !can_merge(&obj("did"), &obj("what")));
+        assert!(can_be_adjacent("what", "did", &lexicon));
+        
+        // "what" is only pronounced at its landing site, never after "saw"
+        assert!(can_be_adjacent("did", "saw", &lexicon));
+        assert!(!can_be_adjacent("saw", "what", &lexicon));
+        assert!(!can_be_adjacent("did", "what", &lexicon));
+        
+        // "b b" first occurs in a a b b, which takes more items than an
+        // enumeration of small derivations would try
+        let lexicon = an_bn_lexicon();
+        assert!(can_be_adjacent("b", "b", &lexicon));
+        assert!(can_be_adjacent("a", "a", &lexicon));
+        assert!(!can_be_adjacent("b", "a", &lexicon));
+    }
+
+    #[test]
+    fn test_surface_orders() {
+        let leaf = |phon: &str, feats: &[Feature]| SyntacticObject::from_lex(&LexItem::new(phon, feats));
+        let optional_sel = |cat| Feature::Optional(Box::new(Feature::Sel(cat)));
+        
+        // Only the command can select the state, so the order is fixed
+        let fixed = [
+            leaf("VOLTAGE_SPIKE", &[Feature::Cat(Category::State)]),
+            leaf("MOTOR_CMD_START", &[Feature::Cat(Category::Command), Feature::Sel(Category::State)]),
+        ];
+        assert_eq!(surface_orders(&fixed), vec![vec!["MOTOR_CMD_START", "VOLTAGE_SPIKE"]]);
+        
+        // Either may optionally select the other, so both orders are possible
+        let free = [
+            leaf("CURRENT_DRAW", &[Feature::Cat(Category::State), optional_sel(Category::Command)]),
+            leaf("MOTOR_CMD_START", &[Feature::Cat(Category::Command), optional_sel(Category::State)]),
+        ];
+        assert_eq!(surface_orders(&free), vec![
+            vec!["CURRENT_DRAW", "MOTOR_CMD_START"],
+            vec!["MOTOR_CMD_START", "CURRENT_DRAW"],
+        ]);
+    }
+
+    #[test]
+    fn test_find_redundant_entries() {
+        let mut lexicon = telemetry_chain_lexicon();
+        lexicon.push(LexItem::new("VOLTAGE_SPIKE", &[Feature::Cat(Category::State)]));
+        
+        // The three state-chaining events share one bundle; the copy-pasted
+        // VOLTAGE_SPIKE duplicates the original
+        assert_eq!(find_redundant_entries(&lexicon), vec![(2, 3), (2, 4), (3, 4), (5, 6)]);
+        assert!(find_redundant_entries(&test_lexicon()[..3]).contains(&(0, 1)));
+    }
+
+    #[test]
+    fn test_merge_only_mode() {
+        let lexicon = wh_question_lexicon();
+        let workspace = |merge_only: bool| {
+            let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+            ws.merge_only = merge_only;
+            for item in &lexicon {
+                ws.add_lex(item);
+            }
+            ws
+        };
+        
+        assert!(derive(&mut workspace(false), DEFAULT_MAX_STEPS).is_ok());
+        
+        let mut ws = workspace(true);
+        assert_eq!(derive(&mut ws, DEFAULT_MAX_STEPS), Err(DerivationError::NoValidOperations));
+        assert_eq!(ws.move_attempts(), 0);
+        assert!(!is_recoverable(&workspace(true), DEFAULT_MAX_STEPS, &mut BTreeMap::new()));
+        
+        // Grammars without movement are unaffected
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT).with_merge_only();
+        ws.add_lex(&test_lexicon()[0]);
+        ws.add_lex(&test_lexicon()[2]);
+        assert!(derive(&mut ws, DEFAULT_MAX_STEPS).is_ok());
+    }
+
+    #[test]
+    fn test_parse_traced_replays() {
+        let lexicon = wh_question_lexicon();
+        let sentence = "saw what did";
+        
+        let (tree, log) = parse_traced(sentence, &lexicon).unwrap();
+        assert_eq!(log, vec![
+            StepKind::Merge { selector: 0, selectee: 1 },
+            StepKind::Merge { selector: 0, selectee: 1 },
+            StepKind::Move { item: 0 },
+        ]);
+        
+        let tokens: Vec<&str> = sentence.split_whitespace().collect();
+        let mut replay = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        load_tokens(&mut replay, &tokens, &lexicon).unwrap();
+        apply_script(&mut replay, &log).unwrap();
+        assert_eq!(replay.items, vec![tree]);
+        
+        let mut bad = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        load_tokens(&mut bad, &tokens, &lexicon).unwrap();
+        assert_eq!(apply_script(&mut bad, &[StepKind::Move { item: 7 }]), Err(DerivationError::InvalidOperation));
+        
+        // A rejected merge leaves both items in place
+        let mut rejected = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        load_tokens(&mut rejected, &["the", "left"], &test_lexicon()).unwrap();
+        let before = rejected.items.clone();
+        let script = [StepKind::Merge { selector: 0, selectee: 1 }];
+        assert!(matches!(apply_script(&mut rejected, &script), Err(DerivationError::FeatureMismatch { .. })));
+        assert_eq!(rejected.items, before);
+        assert!(apply_operation(&mut rejected, &script[0]).is_err());
+        assert_eq!(rejected.items, before);
+    }
+
+    #[test]
+    fn test_derive_traced_replays() {
+        let lexicon = wh_question_lexicon();
+        let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT).with_merge_policy(MergePolicy::FewestFeatures);
+        for item in lexicon.iter().rev() {
+            workspace.add_lex(item);
+        }
+        let initial = workspace.clone();
+        
+        let (tree, log) = derive_traced(&mut workspace, DEFAULT_MAX_STEPS).unwrap();
+        assert_eq!(log.last(), Some(&Operation::Move { item: 0 }));
+        
+        let mut replay = initial;
+        apply_script(&mut replay, &log).unwrap();
+        assert_eq!(replay.items, vec![tree]);
+    }
+
+    #[test]
+    fn test_custom_node_cost() {
+        fn phon_bytes(obj: &SyntacticObject) -> usize {
+            obj.phon.as_ref().map_or(0, |phon| phon.len())
+        }
+        let lexicon = test_lexicon();
+        let load = |ws: &mut Workspace| load_tokens(ws, &["the", "student"], &lexicon).unwrap();
+        
+        let mut uniform = Workspace::new(DEFAULT_MEMORY_LIMIT).with_node_cost(uniform_node_cost);
+        load(&mut uniform);
+        assert_eq!(uniform.memory_usage(), 2);
+        
+        let mut weighted = Workspace::new(DEFAULT_MEMORY_LIMIT).with_node_cost(phon_bytes);
+        load(&mut weighted);
+        assert_eq!(weighted.memory_usage(), 10);
+        
+        // Internal nodes are free under this weighting
+        derive(&mut weighted, DEFAULT_MAX_STEPS).unwrap();
+        assert_eq!(weighted.memory_usage(), 10);
+        
+        let mut tight = Workspace::new(9).with_node_cost(phon_bytes);
+        load(&mut tight);
+        assert_eq!(derive(&mut tight, DEFAULT_MAX_STEPS), Err(DerivationError::MemoryLimitExceeded));
+    }
+
+    #[test]
+    fn test_memory_usage_counts_allocations() {
+        let lexicon = vec![
+            LexItem::new("CURRENT_DRAW_SENSOR_READING", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
+            LexItem::new("VOLTAGE_SPIKE", &[Feature::Cat(Category::State)]),
+        ];
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        load_tokens(&mut ws, &["CURRENT_DRAW_SENSOR_READING", "VOLTAGE_SPIKE"], &lexicon).unwrap();
+        
+        assert_eq!(ws.node_count(), 2);
+        assert_eq!(ws.memory_usage(), (1 + 27 + 2) + (1 + 13 + 1));
+        
+        // The limit check in `step` sees the richer estimate
+        let mut tight = Workspace::new(ws.node_count() + 1);
+        tight.items = ws.items.clone();
+        assert_eq!(step(&mut tight), Err(DerivationError::MemoryLimitExceeded));
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let lexicon = vec![
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::N)]),
+            LexItem::new("spies", &[Feature::Cat(Category::N), Feature::Sel(Category::D)]),
+            LexItem::new("there", &[Feature::Cat(Category::D)]),
+        ];
+        
+        let parses = parse_all("saw spies there", &lexicon, DEFAULT_MAX_STEPS);
+        assert_eq!(parses.len(), 2);
+        assert!(parses.iter().all(|t| t.is_saturated()));
+        assert!(parses.iter().any(|t| t.structurally_equivalent(&parse_sentence("saw spies there", &lexicon).unwrap())));
+        
+        // Too few steps to finish either derivation
+        assert!(parse_all("saw spies there", &lexicon, 1).is_empty());
+        assert!(parse_all("saw unicorns", &lexicon, DEFAULT_MAX_STEPS).is_empty());
+    }
+
+    #[test]
+    fn test_count_derivations() {
+        let lexicon = vec![
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::N)]),
+            LexItem::new("spies", &[Feature::Cat(Category::N), Feature::Sel(Category::D)]),
+            LexItem::new("there", &[Feature::Cat(Category::D)]),
+        ];
+        assert_eq!(count_derivations("saw spies there", &lexicon, DEFAULT_MAX_STEPS), 2);
+        
+        // Trees are counted by structure, whatever their feature bundles
+        let tree = parse_sentence("saw spies there", &lexicon).unwrap();
+        let bare = SyntacticObject::internal(tree.label.clone(), Vec::new(), tree.children.clone());
+        assert!(bare.structurally_equivalent(&tree));
+        assert_eq!(bare.structure_key(), tree.structure_key());
+        assert_ne!(tree.children[1].structure_key(), tree.structure_key());
+        
+        let lexicon = test_lexicon();
+        assert_eq!(count_derivations("the student", &lexicon, DEFAULT_MAX_STEPS), 1);
+        assert_eq!(count_derivations("the the", &lexicon, DEFAULT_MAX_STEPS), 0);
+        assert_eq!(count_derivations("the unicorn", &lexicon, DEFAULT_MAX_STEPS), 0);
+    }
+
+    #[test]
+    fn test_merge_policy() {
+        let lexicon = vec![
+            LexItem::new("c1", &[Feature::Cat(Category::C), Feature::Sel(Category::D)]),
+            LexItem::new("d2", &[Feature::Cat(Category::D), Feature::Sel(Category::C)]),
+            LexItem::new("d1", &[Feature::Cat(Category::D)]),
+        ];
+        let run = |policy: MergePolicy| {
+            let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT).with_merge_policy(policy);
+            load_tokens(&mut ws, &["c1", "d2", "d1"], &lexicon).unwrap();
+            derive_traced(&mut ws, DEFAULT_MAX_STEPS)
+        };
+        
+        // The default policy is stable across runs
+        assert_eq!(Workspace::new(DEFAULT_MEMORY_LIMIT).merge_policy, MergePolicy::Leftmost);
+        assert_eq!(run(MergePolicy::Leftmost), run(MergePolicy::Leftmost));
+        assert_eq!(run(MergePolicy::Leftmost), Err(DerivationError::NoValidOperations));
+        
+        // Merging c1 with the bare d1 leaves fewer features, and succeeds
+        let (tree, log) = run(MergePolicy::FewestFeatures).unwrap();
+        assert_eq!(log[0], StepKind::Merge { selector: 0, selectee: 2 });
+        assert_eq!(tree.linearize(), "d2 c1 d1");
+        assert_eq!(run(MergePolicy::FewestFeatures).unwrap().1, log);
+    }
+
+    #[test]
+    fn test_to_bracketed() {
+        let lexicon = test_lexicon();
+        let dp = parse_sentence("the student", &lexicon).unwrap();
+        assert_eq!(dp.to_bracketed(), "[D [D the] [N student]]");
+        
+        let lexicon = wh_question_lexicon();
+        let moved = parse_sentence("saw what did", &lexicon).unwrap();
+        assert_eq!(moved.to_bracketed(), "[S [D what]_1 [S [S did] [V [V saw] t_1]]]");
+        
+        // Successive movement: the intermediate trace continues the chain
+        let lexicon = vec![
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1), Feature::Neg(2)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V), Feature::Pos(1)]),
+            LexItem::new("focus", &[Feature::Cat(Category::C), Feature::Sel(Category::S), Feature::Pos(2)]),
+        ];
+        let tree = parse_sentence("saw what did focus", &lexicon).unwrap();
+        assert_eq!(tree.to_bracketed(), "[C [D what]_1 [C [C focus] [S t_1 [S [S did] [V [V saw] t_1]]]]]");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let lexicon = test_lexicon();
+        let dp = parse_sentence("the student", &lexicon).unwrap();
+        assert_eq!(
+            dp.to_dot(),
+            "digraph {\n    n0 [label=\"D\"];\n    n1 [label=\"D\\nthe\"];\n    n0 -> n1;\n    n2 [label=\"N\\nstudent\"];\n    n0 -> n2;\n}\n"
+        );
+        assert!(dp.to_dot_with_features().contains("n1 [label=\"D\\nthe\\n[Cat(D), Sel(N)]\"];"));
+        
+        // A stuck workspace renders as a forest with its leftover features
+        let (result, ws) = parse_sentence_debug("the the", &lexicon);
+        assert!(result.is_err());
+        let dot = ws.to_dot();
+        assert_eq!(dot.matches("[label=").count(), 2);
+        assert!(!dot.contains("->"));
+        assert!(dot.contains("Sel(N)"));
+        
+        let leaf = SyntacticObject::from_lex(&LexItem::new("say \"hi\"", &[Feature::Cat(Category::N)]));
+        assert!(leaf.to_dot().contains(r#"[label="N\nsay \"hi\""];"#));
+    }
+
+    #[test]
+    fn test_parse_lexicon() {
+        let src = "# determiners\nthe :: D =N\n\nstudent :: N\nwhat :: D -1\ndid :: S =V +1\n";
+        let lexicon = parse_lexicon(src).unwrap();
+        assert_eq!(lexicon, vec![
+            LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+            LexItem::new("student", &[Feature::Cat(Category::N)]),
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1)]),
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V), Feature::Pos(1)]),
+        ]);
+        assert!(parse_sentence("the student", &lexicon).is_ok());
+        
+        let err = parse_lexicon("the :: D =N\na :: D =X").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 9));
+        assert_eq!(err.to_string(), "2:9: unknown category `X`");
+        
+        let err = parse_lexicon("  student N").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        
+        let err = parse_lexicon("what :: D -x").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 12));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let lexicon = wh_question_lexicon();
+        let mut tree = parse_sentence("saw what did", &lexicon).unwrap();
+        tree.features.push(Feature::Ctx("DRIVE".to_string()));
+        let json = serde_json::to_string(&tree).unwrap();
+        let back: SyntacticObject = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, tree);
+        assert!(back.features.contains(&Feature::Ctx("DRIVE".to_string())));
+        
+        let json = serde_json::to_string(&lexicon).unwrap();
+        let back: Vec<LexItem> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, lexicon);
+        
+        // Lexicons written before the key was renamed still load
+        let old = r#"{ "phon": "what", "feats": [{ "Cat": "D" }, { "Neg": 1 }], "weight": 1.0 }"#;
+        assert_eq!(serde_json::from_str::<LexItem>(old).unwrap(), lexicon[0]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_grammar_json() {
+        let src = r#"{
+            "lexicon": [
+                { "phon": "the", "features": [{ "Cat": "D" }, { "Sel": "N" }] },
+                { "phon": "student", "features": [{ "Cat": "N" }], "weight": 0.5 }
+            ],
+            "start_category": "D"
+        }"#;
+        let grammar = Grammar::from_json(src).unwrap();
+        assert_eq!(grammar.lexicon[0], test_lexicon()[0]);
+        assert_eq!(grammar.lexicon[1].weight, 0.5);
+        assert!(grammar.parse("the student").is_ok());
+        
+        let json = grammar.to_json().unwrap();
+        assert!(json.contains("\"features\""));
+        assert_eq!(Grammar::from_json(&json).unwrap(), grammar);
+        assert!(Grammar::from_json(r#"{ "lexicon": [] }"#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_rejects_invalid_weight() {
+        for weight in ["0", "-0.5"] {
+            let src = format!(r#"{{ "phon": "x", "features": [{{ "Cat": "D" }}], "weight": {} }}"#, weight);
+            let err = serde_json::from_str::<LexItem>(&src).unwrap_err();
+            assert!(err.to_string().contains("lexical weight must be positive and finite"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_grammar_start_category() {
+        let grammar = Grammar::new(test_lexicon(), Category::V);
+        assert!(grammar.parse("smiled").is_ok());
+        assert_eq!(
+            grammar.parse("the student"),
+            Err(DerivationError::WrongStartCategory { expected: Category::V, found: Category::D }),
+        );
+        assert_eq!(grammar.parse("the"), Err(DerivationError::NoValidOperations));
+    }
+
+    #[test]
+    fn test_start_category() {
+        let lexicon = test_lexicon();
+        let fragment = |mut ws: Workspace| {
+            load_tokens(&mut ws, &["the", "student"], &lexicon).unwrap();
+            ws
+        };
+        
+        let mut ws = fragment(Workspace::new(DEFAULT_MEMORY_LIMIT));
+        assert_eq!(derive(&mut ws, DEFAULT_MAX_STEPS).unwrap().label, Category::D);
+        
+        // The DP is complete but is not a sentence
+        let mut ws = fragment(Workspace::new(DEFAULT_MEMORY_LIMIT).with_start_category(Category::S));
+        assert_eq!(
+            derive(&mut ws, DEFAULT_MAX_STEPS),
+            Err(DerivationError::WrongStartCategory { expected: Category::S, found: Category::D }),
+        );
+        assert_eq!(ws.items.len(), 1);
+        assert!(ws.items[0].is_saturated());
+        assert!(!ws.is_successful());
+        
+        let mut ws = fragment(Workspace::new(DEFAULT_MEMORY_LIMIT).with_start_category(Category::D));
+        assert!(derive(&mut ws, DEFAULT_MAX_STEPS).is_ok());
+    }
+
+    #[test]
+    fn test_space_operations_grammar() {
+        let lexicon = space_operations_lexicon();
+        let tree = parse_sentence("MOTOR_CMD_START CURRENT_DRAW VOLTAGE_SPIKE", &lexicon).unwrap();
+        assert_eq!(tree.label, Category::Command);
+        
+        // Every adjacent pair is licensed, but the chain is left open
+        assert!(parse_sentence("MOTOR_CMD_START CURRENT_DRAW", &lexicon).is_err());
+        
+        // A context selects the command that follows it, but not a state
+        let tree = parse_sentence("CTX_DRIVE MOTOR_CMD_START VOLTAGE_SPIKE", &lexicon).unwrap();
+        assert_eq!(tree.label, Category::Context);
+        assert!(parse_sentence("CTX_STANDBY VOLTAGE_SPIKE", &lexicon).is_err());
+    }
+
+    #[test]
+    fn test_max_move_depth() {
+        // A mover buried under a long chain of nodes that all carry its feature
+        let mut chain = SyntacticObject::from_lex(&LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1)]));
+        for _ in 0..1000 {
+            chain = SyntacticObject::internal(Category::D, vec![Feature::Cat(Category::D), Feature::Neg(1)], vec![chain]);
+        }
+        let obj = SyntacticObject::internal(Category::S, vec![Feature::Cat(Category::S), Feature::Pos(1)], vec![chain]);
+        
+        assert_eq!(move_operation_bounded(obj.clone(), 100), Err(DerivationError::MemoryLimitExceeded));
+        assert!(move_operation_bounded(obj.clone(), 2000).is_ok());
+        
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT).with_max_move_depth(100);
+        ws.items.push(obj);
+        assert_eq!(step(&mut ws), Err(DerivationError::MemoryLimitExceeded));
+    }
+
+    #[test]
+    fn test_multiple_movement_chains() {
+        // "did" triggers +1 and +2, checked by two independent movers
+        let lexicon = vec![
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("whom", &[Feature::Cat(Category::N), Feature::Sel(Category::V), Feature::Neg(2)]),
+            LexItem::new("did", &[
+                Feature::Cat(Category::S),
+                Feature::Sel(Category::N),
+                Feature::Pos(1),
+                Feature::Pos(2),
+            ]),
+        ];
+        
+        let (tree, log) = parse_traced("saw what whom did", &lexicon).unwrap();
+        assert_eq!(tree.features, vec![Feature::Cat(Category::S)]);
+        assert_eq!(&log[log.len() - 2..], &[StepKind::Move { item: 0 }, StepKind::Move { item: 0 }]);
+        
+        // Chain 1 fronted "what", then chain 2 fronted the phrase headed by "whom"
+        assert_eq!(tree.children[0].lexical_head().phon.as_deref(), Some("whom"));
+        assert_eq!(tree.children[1].children[0].phon.as_deref(), Some("what"));
+    }
+
+    #[test]
+    fn test_movement_chains() {
+        // "who" moves once, from the subject position to the CP edge
+        let tree = parse_sentence("who left", &wh_movement_lexicon()).unwrap();
+        assert_eq!(tree.movement_chains(), vec![MovementChain {
+            index: 1,
+            landing: vec![0],
+            trace: Some(vec![1, 1, 1]),
+        }]);
+        assert!(tree.children[1].children[1].children[1].is_trace());
+        
+        // Successive movement: the second chain's trace is the first's landing site
+        let lexicon = vec![
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1), Feature::Neg(2)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V), Feature::Pos(1)]),
+            LexItem::new("focus", &[Feature::Cat(Category::C), Feature::Sel(Category::S), Feature::Pos(2)]),
+        ];
+        let tree = parse_sentence("saw what did focus", &lexicon).unwrap();
+        assert_eq!(tree.movement_chains(), vec![
+            MovementChain { index: 2, landing: vec![0], trace: Some(vec![1, 1, 0]) },
+            MovementChain { index: 1, landing: vec![1, 1, 0], trace: Some(vec![1, 1, 1, 1, 1]) },
+        ]);
+        
+        // Nothing moved
+        assert!(parse_sentence("the student", &test_lexicon()).unwrap().movement_chains().is_empty());
+    }
+
+    #[test]
+    fn test_movement_leaves_trace() {
+        let lexicon = vec![
+            LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N), Feature::Neg(1)]),
+            LexItem::new("student", &[Feature::Cat(Category::N)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V), Feature::Pos(1)]),
+        ];
+        let tree = parse_sentence("the student saw did", &lexicon).unwrap();
+        
+        // The moved DP is pronounced once, at its landing site
+        assert_eq!(tree.linearize(), "the student did saw");
+        assert_eq!(tree.linearize().matches("student").count(), 1);
+        
+        // Its original site holds a silent D trace the verb still selects
+        let vp = &tree.children[1].children[1];
+        assert_eq!(vp.children[1], SyntacticObject::trace(Category::D, 1));
+        assert_eq!(vp.lexical_head().phon.as_deref(), Some("saw"));
+        assert_eq!(tree.to_ptb(), "(S (D (D the) (N student)) (S (S did) (V (V saw) (-NONE- *T*))))");
+    }
+
+    #[test]
+    fn test_derivation_iterator() {
+        let lexicon = test_lexicon();
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        load_tokens(&mut ws, &["the", "student"], &lexicon).unwrap();
+        
+        let states: Vec<Workspace> = DerivationIterator::new(ws.clone()).map(Result::unwrap).collect();
+        assert_eq!(states.len(), 1);
+        assert!(states[0].is_successful());
+        assert_eq!(states[0].items[0], derive(&mut ws, DEFAULT_MAX_STEPS).unwrap());
+        
+        // A stuck derivation stops without an error once nothing applies
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        load_tokens(&mut ws, &["the", "student", "the"], &lexicon).unwrap();
+        let states: Vec<_> = DerivationIterator::new(ws).collect();
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].as_ref().unwrap().items.len(), 2);
+        
+        // Other errors are reported once
+        let mut ws = Workspace::new(0);
+        load_tokens(&mut ws, &["the", "student"], &lexicon).unwrap();
+        let mut states = DerivationIterator::new(ws);
+        assert_eq!(states.next().unwrap().unwrap_err(), DerivationError::MemoryLimitExceeded);
+        assert!(states.next().is_none());
+    }
+
+    #[test]
+    fn test_adjunction() {
+        let red = SyntacticObject::from_lex(&LexItem::new("red", &[Feature::Adj(Category::N)]));
+        let student = SyntacticObject::from_lex(&LexItem::new("student", &[Feature::Cat(Category::N)]));
+        
+        // Either argument order adjoins "red" before its host
+        let np = merge(red.clone(), student.clone()).unwrap();
+        assert_eq!(merge(student.clone(), red.clone()).unwrap(), np);
+        assert_eq!(np.label, Category::N);
+        assert_eq!(np.features, vec![Feature::Cat(Category::N)]);
+        assert_eq!(np.linearize(), "red student");
+        assert_eq!(np.lexical_head().phon.as_deref(), Some("student"));
+        
+        // The host's category is unchanged, so it can still be selected
+        let lexicon = vec![
+            LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+            LexItem::new("red", &[Feature::Adj(Category::N)]),
+            LexItem::new("student", &[Feature::Cat(Category::N)]),
+        ];
+        let dp = parse_sentence("red student the", &lexicon).unwrap();
+        assert_eq!(dp.label, Category::D);
+        assert_eq!(dp.linearize(), "the red student");
+        
+        // Adjuncts only attach to their own category
+        let verb = SyntacticObject::from_lex(&LexItem::new("left", &[Feature::Cat(Category::V)]));
+        assert!(!can_merge(&red, &verb));
+        assert_eq!(merge_adjoin(red, verb), Err(DerivationError::FeatureMismatch {
+            expected: Some(Feature::Adj(Category::V)),
+            found: Some(Feature::Adj(Category::N)),
+        }));
+    }
+
+    #[test]
+    fn test_parse_sentence_with() {
+        let lexicon = test_lexicon();
+        let with = |policy: UnknownTokenPolicy| ParseOptions { unknown_tokens: policy };
+        
+        assert_eq!(
+            parse_sentence_with("the tall student", &lexicon, &ParseOptions::default()),
+            Err(DerivationError::UnknownToken { token: "tall".to_string(), index: 1, byte_offset: 4 })
+        );
+        
+        let skipped = parse_sentence_with("the tall student", &lexicon, &with(UnknownTokenPolicy::Skip)).unwrap();
+        assert_eq!(skipped.linearize(), "the student");
+        
+        let wild = parse_sentence_with("the wug", &lexicon, &with(UnknownTokenPolicy::Wildcard(Category::N))).unwrap();
+        assert_eq!(wild.linearize(), "the wug");
+        assert_eq!(wild.label, Category::D);
+        
+        assert_eq!(
+            parse_sentence_with("zorp the wug zorp student", &lexicon, &with(UnknownTokenPolicy::Collect)),
+            Err(DerivationError::UnknownTokens(vec!["zorp".to_string(), "wug".to_string()]))
+        );
+        assert!(parse_sentence_with("the student", &lexicon, &with(UnknownTokenPolicy::Collect)).is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_derivation_error_is_std_error() {
+        fn parse_boxed(sentence: &str) -> Result<SyntacticObject, Box<dyn std::error::Error>> {
+            Ok(parse_sentence(sentence, &test_lexicon())?)
         }
+        
+        let err = parse_boxed("the unicorn").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown token: unicorn (token 1, byte 4)");
+        assert!(err.source().is_none());
+        assert!(parse_boxed("the student").is_ok());
     }
-    
-    derive(&mut workspace, 100) // Max 100 derivation steps
-}
 
-/// Generate string of specified pattern
-pub fn generate_pattern(pattern: &str, n: usize) -> Result<String, DerivationError> {
-    match pattern {
-        "an_bn" => Ok(generate_an_bn(n)),
-        _ => Err(DerivationError::InvalidOperation),
+    #[test]
+    fn test_derive_weighted() {
+        let lexicon = vec![
+            LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+            LexItem::new("run", &[Feature::Cat(Category::V)]).with_weight(0.3),
+            LexItem::new("run", &[Feature::Cat(Category::N)]).with_weight(0.6),
+        ];
+        
+        // Both readings of "run" derive; the likelier noun wins
+        let (tree, score) = derive_weighted("run", &lexicon, DEFAULT_MAX_STEPS).unwrap();
+        assert_eq!(tree.label, Category::N);
+        assert!((score - 0.6).abs() < 1e-12);
+        
+        // Favouring the verb flips the bare reading, but only the noun fits
+        // after "the"
+        let lexicon = vec![
+            lexicon[0].clone(),
+            lexicon[1].clone().with_weight(0.9),
+            lexicon[2].clone().with_weight(0.1),
+        ];
+        let (tree, score) = derive_weighted("run", &lexicon, DEFAULT_MAX_STEPS).unwrap();
+        assert_eq!(tree.label, Category::V);
+        assert!((score - 0.9).abs() < 1e-12);
+        let (tree, score) = derive_weighted("the run", &lexicon, DEFAULT_MAX_STEPS).unwrap();
+        assert_eq!(tree.label, Category::D);
+        assert!((score - 0.1).abs() < 1e-12);
+        
+        assert_eq!(derive_weighted("the the", &lexicon, DEFAULT_MAX_STEPS), Err(DerivationError::NoValidOperations));
+        assert_eq!(derive_weighted("a run", &lexicon, DEFAULT_MAX_STEPS), Err(DerivationError::UnknownToken { token: "a".to_string(), index: 0, byte_offset: 0 }));
     }
-}
 
-/// Check if grammar can generate given string
-pub fn can_generate(pattern: &str, n: usize) -> bool {
-    generate_pattern(pattern, n).is_ok()
-}
+    #[test]
+    fn test_derive_weighted_finds_best_reading_past_limit() {
+        // 2^13 readings, more than MAX_WEIGHTED_READINGS; in lexicon order
+        // the best one comes last
+        let features = [Feature::Cat(Category::D), Feature::Optional(Box::new(Feature::Sel(Category::D)))];
+        let lexicon = [
+            LexItem::new("x", &features).with_weight(0.4),
+            LexItem::new("x", &features).with_weight(0.6),
+        ];
+        let sentence = ["x"; 13].join(" ");
+        
+        let (_, score) = derive_weighted(&sentence, &lexicon, DEFAULT_MAX_STEPS).unwrap();
+        assert!((score - 0.6f64.powi(13)).abs() < 1e-12);
+    }
 
-// ============================================================================
-// Python Bridge (PyO3)
-// ============================================================================
+    #[test]
+    #[should_panic(expected = "lexical weight must be positive and finite")]
+    fn test_with_weight_rejects_zero() {
+        let _ = LexItem::new("x", &[Feature::Cat(Category::D)]).with_weight(0.0);
+    }
 
-#[cfg(feature = "pyo3")]
-#[pyfunction]
-/// Validates a sequence of telemetry data using a formal grammar.
-/// This is a simple version for basic anomaly detection.
-fn validate_telemetry_sequence(sequence: Vec<f64>) -> PyResult<bool> {
-    // This function is kept for backward compatibility, but the new demo
-    // should use `validate_mission_log`.
-    let is_anomalous = sequence.iter().any(|&v| v > 10.0);
-    Ok(!is_anomalous)
-}
+    #[test]
+    #[should_panic(expected = "lexical weight must be positive and finite")]
+    fn test_builder_weight_rejects_nan() {
+        let _ = LexiconBuilder::new().word("x").cat(Category::D).weight(f64::NAN);
+    }
 
-#[cfg(feature = "pyo3")]
-#[pyfunction]
-/// Validates a structured mission log against a formal grammar of operations.
-/// Returns a list of explanations for any ungrammatical (anomalous) sequences.
-fn validate_mission_log(log: Vec<String>) -> PyResult<Vec<String>> {
-    // --- The Grammar of Space Operations ---
-    let lexicon = vec![
-        // COMMANDS: Actions that can be taken. A command selects a state.
-        LexItem::new("MOTOR_CMD_START", &[Feature::Cat(Category::Command), Feature::Sel(Category::State)]),
-        LexItem::new("MOTOR_CMD_STOP", &[Feature::Cat(Category::Command), Feature::Sel(Category::State)]),
-        LexItem::new("INSTRUMENT_PWR_ON", &[Feature::Cat(Category::Command), Feature::Sel(Category::State)]),
-        LexItem::new("INSTRUMENT_PWR_OFF", &[Feature::Cat(Category::Command), Feature::Sel(Category::State)]),
+    #[test]
+    fn test_incremental_parser() {
+        let lexicon = space_operations_lexicon();
+        let mut parser = IncrementalParser::new(&lexicon);
+        
+        // Each event is folded in on arrival, leaving one open constituent
+        for event in ["CTX_DRIVE", "MOTOR_CMD_START", "CURRENT_DRAW"] {
+            parser.feed(event).unwrap();
+            assert_eq!(parser.current_state().items.len(), 1);
+            assert!(!parser.current_state().is_successful());
+        }
+        parser.feed("VOLTAGE_SPIKE").unwrap();
+        assert!(parser.current_state().is_successful());
+        let tree = &parser.current_state().items[0];
+        assert_eq!(tree.label, Category::Context);
+        assert_eq!(tree.linearize(), "CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW VOLTAGE_SPIKE");
+        
+        // A state with nothing to attach to is stranded as soon as it arrives
+        let mut parser = IncrementalParser::new(&lexicon);
+        parser.feed("CTX_STANDBY").unwrap();
+        parser.feed("VOLTAGE_SPIKE").unwrap();
+        assert_eq!(parser.current_state().items.len(), 2);
+        
+        assert_eq!(
+            parser.feed("WARP_DRIVE"),
+            Err(DerivationError::UnknownToken { token: "WARP_DRIVE".to_string(), index: 2, byte_offset: 26 })
+        );
+        assert_eq!(parser.current_state().items.len(), 2);
+        
+        // Covert heads arrive with the first token
+        let lexicon = wh_movement_lexicon();
+        let mut parser = IncrementalParser::new(&lexicon);
+        parser.feed("who").unwrap();
+        assert_eq!(parser.current_state().items.len(), 2);
+        parser.feed("left").unwrap();
+        assert!(parser.current_state().is_successful());
+        let tree = &parser.current_state().items[0];
+        assert_eq!(tree.label, Category::C);
+        assert_eq!(tree.linearize(), "who left");
+    }
 
-        // STATES: Observations about the system. 
-        // A state can select another state, allowing for a valid chain of telemetry.
-        LexItem::new("VOLTAGE_SPIKE", &[Feature::Cat(Category::State)]), // Terminal state, cannot select another.
-        LexItem::new("CURRENT_DRAW", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
-        LexItem::new("WHEEL_RPM", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
-        LexItem::new("TEMP_MOTOR", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
-        LexItem::new("TEMP_INSTRUMENT", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
-        LexItem::new("SPECTROMETER_READ", &[Feature::Cat(Category::State), Feature::Sel(Category::State)]),
-    ];
-
-    let mut anomalies = Vec::new();
-
-    // We check each 2-event window.
-    for i in 0..log.len() {
-        if i + 1 >= log.len() { break; }
-
-        let prev_event_str = &log[i];
-        let current_event_str = &log[i+1];
-
-        // Find the lexical items for the current window.
-        let prev_lex_item = lexicon.iter().find(|item| item.phon == *prev_event_str);
-        let current_lex_item = lexicon.iter().find(|item| item.phon == *current_event_str);
-
-        if let (Some(prev_item), Some(current_item)) = (prev_lex_item, current_lex_item) {
-            // Create syntactic objects from the lexical items.
-            let prev_obj = SyntacticObject::from_lex(prev_item);
-            let current_obj = SyntacticObject::from_lex(current_item);
-
-            // The core logic: Check if the first event can grammatically select the second.
-            if !can_merge(&prev_obj, &current_obj) {
-                let explanation = format!(
-                    "Anomaly Detected: Ungrammatical sequence '{}' followed by '{}'. This violates operational rules.",
-                    prev_event_str, current_event_str
-                );
-                anomalies.push(explanation);
+    #[test]
+    fn test_validate_structure() {
+        let lexicon = test_lexicon();
+        assert_eq!(parse_sentence("the student", &lexicon).unwrap().validate(), Ok(()));
+        
+        let the = SyntacticObject::from_lex(&lexicon[0]);
+        let silent = SyntacticObject::internal(Category::N, vec![Feature::Cat(Category::N)], Vec::new());
+        let malformed = SyntacticObject::internal(Category::D, Vec::new(), vec![the.clone(), silent]);
+        assert_eq!(malformed.validate(), Err(StructureError::LeafWithoutPhon { path: vec![1] }));
+        
+        let mut pronounced = SyntacticObject::internal(Category::D, Vec::new(), vec![the.clone()]);
+        pronounced.phon = Some("the".into());
+        assert_eq!(pronounced.validate(), Err(StructureError::InternalWithPhon { path: vec![] }));
+        
+        let mut deep = the;
+        for _ in 0..=MAX_STRUCTURE_DEPTH {
+            deep = SyntacticObject::internal(Category::D, Vec::new(), vec![deep]);
+        }
+        assert_eq!(deep.validate(), Err(StructureError::TooDeep { limit: MAX_STRUCTURE_DEPTH }));
+        
+        // Traces are the one kind of silent leaf allowed
+        let moved = SyntacticObject::internal(Category::D, Vec::new(), vec![SyntacticObject::trace(Category::N, 1)]);
+        assert_eq!(moved.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_parse_category_and_feature() {
+        assert_eq!("Command".parse::<Category>(), Ok(Category::Command));
+        assert_eq!("DP".parse::<Category>(), Ok(Category::DP));
+        assert_eq!("Cmd".parse::<Category>(), Err(ParseCategoryError { name: "Cmd".to_string() }));
+        
+        assert_eq!("=State".parse::<Feature>(), Ok(Feature::Sel(Category::State)));
+        assert_eq!("Context".parse::<Feature>(), Ok(Feature::Cat(Category::Context)));
+        assert_eq!("+1".parse::<Feature>(), Ok(Feature::Pos(1)));
+        assert_eq!("-2".parse::<Feature>(), Ok(Feature::Neg(2)));
+        
+        let err = "=X".parse::<Feature>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown category `X`");
+        assert_eq!("-x".parse::<Feature>(), Err(ParseFeatureError::InvalidIndex("x".to_string())));
+    }
+
+    #[test]
+    fn test_recognize() {
+        let lexicon = vec![
+            LexItem::new("c1", &[Feature::Cat(Category::C), Feature::Sel(Category::D)]),
+            LexItem::new("d1", &[Feature::Cat(Category::D)]),
+            LexItem::new("d2", &[Feature::Cat(Category::D), Feature::Sel(Category::C)]),
+        ];
+        
+        // The greedy derivation strands d1 here, but a derivation exists
+        assert!(parse_sentence("c1 d2 d1", &lexicon).is_err());
+        assert!(recognize("c1 d2 d1", &lexicon));
+        assert!(!recognize("c1 d1 d1", &lexicon));
+        assert!(!recognize("c1 d3", &lexicon));
+        assert!(!recognize("", &lexicon));
+        
+        // Whatever the greedy parser accepts, the exhaustive search accepts too
+        let lexicon = test_lexicon();
+        for sentence in ["the student", "student the", "smiled", "the student left"] {
+            if parse_sentence(sentence, &lexicon).is_ok() {
+                assert!(recognize(sentence, &lexicon), "{}", sentence);
             }
-        } else {
-            // Handle cases where a token isn't in the lexicon.
-            let explanation = format!(
-                "Anomaly Detected: Unknown event(s) in sequence ['{}', '{}'].",
-                prev_event_str, current_event_str
-            );
-            anomalies.push(explanation);
+            assert_eq!(recognize(sentence, &lexicon), !parse_all(sentence, &lexicon, DEFAULT_MAX_STEPS).is_empty());
         }
+        
+        // Movement is applied to every object built
+        let lexicon = wh_question_lexicon();
+        assert!(recognize("what did saw", &lexicon));
+        assert!(!recognize("did saw", &lexicon));
+        
+        // Covert heads are seeded once each
+        let lexicon = wh_movement_lexicon();
+        assert!(parse_sentence("who left", &lexicon).is_ok());
+        assert!(recognize("who left", &lexicon));
+        assert!(!recognize("who", &lexicon));
     }
 
-    Ok(anomalies)
-}
+    #[test]
+    fn test_find_mission_log_anomalies() {
+        let lexicon = space_operations_lexicon();
+        let log: Vec<String> = ["CTX_DRIVE", "MOTOR_CMD_START", "VOLTAGE_SPIKE", "GLITCH"]
+            .iter().map(|s| s.to_string()).collect();
+        assert_eq!(find_mission_log_anomalies(&log[..3], &lexicon), vec![]);
+        
+        let anomalies = find_mission_log_anomalies(&log, &lexicon);
+        assert_eq!(anomalies, vec![MissionLogAnomaly {
+            index: 2,
+            previous: "VOLTAGE_SPIKE".to_string(),
+            current: "GLITCH".to_string(),
+            reason: AnomalyReason::UnknownToken,
+            mismatch: None,
+        }]);
+        
+        let log = vec!["CTX_STANDBY".to_string(), "VOLTAGE_SPIKE".to_string()];
+        let anomalies = find_mission_log_anomalies(&log, &lexicon);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].reason, AnomalyReason::UngrammaticalSequence);
+        assert_eq!(anomalies[0].mismatch, Some(DerivationError::FeatureMismatch {
+            expected: Some(Feature::Sel(Category::Command)),
+            found: Some(Feature::Cat(Category::State)),
+        }));
+    }
 
+    #[test]
+    fn test_mission_log_anomaly_display() {
+        // The text validate_mission_log and validate_mission_logs return
+        let log = vec!["CTX_STANDBY".to_string(), "VOLTAGE_SPIKE".to_string()];
+        let anomalies = find_mission_log_anomalies(&log, &space_operations_lexicon());
+        assert_eq!(
+            anomalies[0].to_string(),
+            "Anomaly Detected: Ungrammatical sequence 'CTX_STANDBY' followed by 'VOLTAGE_SPIKE'. \
+             This violates operational rules (Feature mismatch: expected Sel(Command), found Cat(State)).",
+        );
+        
+        let unknown = MissionLogAnomaly {
+            index: 2,
+            previous: "VOLTAGE_SPIKE".to_string(),
+            current: "GLITCH".to_string(),
+            reason: AnomalyReason::UnknownToken,
+            mismatch: None,
+        };
+        assert_eq!(unknown.to_string(), "Anomaly Detected: Unknown event(s) in sequence ['VOLTAGE_SPIKE', 'GLITCH'].");
+    }
 
-#[cfg(feature = "pyo3")]
-#[pymodule]
-/// Python module for the Atomic Language Model.
-fn atomic_lang_model_python(_py: Python, m: &PyModule) -> PyResult<()> {
-    m.add_function(wrap_pyfunction!(validate_telemetry_sequence, m)?)?;
-    m.add_function(wrap_pyfunction!(validate_mission_log, m)?)?;
-    Ok(())
-}
+    #[test]
+    fn test_display_linearizes() {
+        let tree = parse_sentence("the student", &test_lexicon()).unwrap();
+        assert_eq!(format!("{}", tree), tree.linearize());
+        assert_eq!(tree.to_string(), "the student");
+    }
 
+    #[test]
+    fn test_linearize_with() {
+        let lexicon = vec![
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+            LexItem::new("student", &[Feature::Cat(Category::N)]),
+        ];
+        let tree = parse_sentence("the student saw", &lexicon).unwrap();
+        
+        let head_initial = LinearOrder::new();
+        assert_eq!(tree.linearize_with(&head_initial), tree.linearize());
+        assert_eq!(tree.linearize_with(&head_initial), "saw the student");
+        
+        let head_final = LinearOrder::new().with_default(HeadDirection::Final);
+        assert_eq!(tree.linearize_with(&head_final), "student the saw");
+        
+        // Only the verb is head-final
+        let verb_final = LinearOrder::new().with_direction(Category::V, HeadDirection::Final);
+        assert_eq!(verb_final.direction(&Category::D), HeadDirection::Initial);
+        assert_eq!(tree.linearize_with(&verb_final), "the student saw");
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_step_keeps_selector_role() {
+        let lexicon = test_lexicon();
+        
+        // Whichever index the selector sits at, it stays the first merge operand
+        for words in [["student", "the"], ["the", "student"]] {
+            let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+            for word in words {
+                ws.add_lex(lexicon.iter().find(|item| item.phon == word).unwrap());
+            }
+            
+            step(&mut ws).unwrap();
+            assert_eq!(ws.items.len(), 1);
+            assert_eq!(ws.items[0].label, Category::D);
+            assert_eq!(ws.items[0].children[0].phon.as_deref(), Some("the"));
+            assert!(ws.is_successful());
+        }
+    }
 
     #[test]
-    fn test_feature_operations() {
-        let pos_feat = Feature::Pos(1);
-        let neg_feat = Feature::Neg(1);
+    fn test_wh_movement_who_left() {
+        // The silent C is loaded with the words
+        let (result, ws) = parse_sentence_debug("who left", &wh_movement_lexicon());
+        let tree = result.unwrap();
+        assert_eq!(ws.move_successes(), 1);
+        assert_eq!(tree.label, Category::C);
+        assert!(tree.is_saturated());
+        assert_eq!(tree.validate(), Ok(()));
         
-        assert!(pos_feat.is_positive());
-        assert!(!pos_feat.is_negative());
-        assert!(!neg_feat.is_positive());
-        assert!(neg_feat.is_negative());
+        // "who" is pronounced at the CP edge, leaving a trace inside the VP
+        assert_eq!(tree.children[0].phon.as_deref(), Some("who"));
+        assert_eq!(tree.linearize(), "who left");
+        assert_eq!(tree.to_bracketed(), "[C [D who]_1 [C [C ε] [V [V left] t_1]]]");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_incremental_parser() {
+        let lexicon = space_operations_lexicon();
+        let log: Vec<String> = ["CTX_DRIVE", "MOTOR_CMD_START", "CURRENT_DRAW", "VOLTAGE_SPIKE", "CTX_STANDBY", "VOLTAGE_SPIKE"]
+            .iter().map(|s| s.to_string()).collect();
         
-        assert_eq!(pos_feat.movement_index(), Some(1));
-        assert_eq!(neg_feat.movement_index(), Some(1));
+        let (mut parser, mut anomalies) = AsyncIncrementalParser::new(&lexicon);
+        let mut sync_parser = IncrementalParser::new(&lexicon);
+        for event in &log {
+            parser.feed(event).await.unwrap();
+            sync_parser.feed(event).unwrap();
+            assert_eq!(parser.current_state().items, sync_parser.current_state().items);
+        }
+        assert_eq!(
+            parser.feed("WARP_DRIVE").await,
+            Err(DerivationError::UnknownToken { token: "WARP_DRIVE".to_string(), index: 6, byte_offset: 79 })
+        );
+        drop(parser);
+        
+        let mut received = Vec::new();
+        while let Some(anomaly) = anomalies.recv().await {
+            received.push(anomaly);
+        }
+        let log = [log, vec!["WARP_DRIVE".to_string()]].concat();
+        assert_eq!(received, find_mission_log_anomalies(&log, &lexicon));
+        assert_eq!(received.last().unwrap().reason, AnomalyReason::UnknownToken);
     }
 
+    #[cfg(feature = "rayon")]
     #[test]
-    fn test_an_bn_generation() {
-        assert_eq!(generate_an_bn(0), "");
-        assert_eq!(generate_an_bn(1), "a b");
-        assert_eq!(generate_an_bn(2), "a a b b");
-        assert_eq!(generate_an_bn(3), "a a a b b b");
+    fn test_parallel_parse_all_matches_serial() {
+        let lexicon = vec![
+            LexItem::new("d", &[Feature::Cat(Category::D), Feature::Optional(Box::new(Feature::Sel(Category::D)))]),
+            LexItem::new("e", &[Feature::Cat(Category::D)]),
+        ];
+        let sentence = "d d e d";
+        
+        let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        load_tokens(&mut workspace, &["d", "d", "e", "d"], &lexicon).unwrap();
+        let mut serial = Vec::new();
+        all_derivations(&workspace, DEFAULT_MAX_STEPS, MAX_PARSES, &mut serial);
+        
+        let parallel = parse_all(sentence, &lexicon, DEFAULT_MAX_STEPS);
+        assert!(parallel.len() > 1);
+        assert_eq!(parallel, serial);
+        
+        // A cap smaller than the number of parses keeps the same prefix
+        let mut capped = Vec::new();
+        all_derivations_parallel(&workspace, DEFAULT_MAX_STEPS, 2, &mut capped);
+        assert_eq!(capped, serial[..2]);
     }
 
     #[test]
-    fn test_an_bn_recognition() {
-        assert!(is_an_bn_pattern(""));
-        assert!(is_an_bn_pattern("a b"));
-        assert!(is_an_bn_pattern("a a b b"));
-        assert!(is_an_bn_pattern("a a a b b b"));
+    fn test_select_any_category() {
+        let knows = LexItem::new("knows", &[Feature::Cat(Category::V), Feature::SelAny(vec![Category::DP, Category::CP])]);
+        let it = LexItem::new("it", &[Feature::Cat(Category::DP)]);
+        let whether = LexItem::new("whether", &[Feature::Cat(Category::CP)]);
+        let student = LexItem::new("student", &[Feature::Cat(Category::N)]);
+        let lexicon = vec![knows.clone(), it.clone(), whether.clone(), student.clone()];
         
-        assert!(!is_an_bn_pattern("a"));
-        assert!(!is_an_bn_pattern("a a b"));
-        assert!(!is_an_bn_pattern("a b b"));
+        for complement in [&it, &whether] {
+            let (a, b) = (SyntacticObject::from_lex(&knows), SyntacticObject::from_lex(complement));
+            assert!(can_merge(&a, &b));
+            let merged = merge(a, b).unwrap();
+            assert_eq!(merged.label, Category::V);
+            assert!(merged.is_saturated());
+        }
+        assert!(!can_merge(&SyntacticObject::from_lex(&knows), &SyntacticObject::from_lex(&student)));
+        assert!(parse_sentence("knows whether", &lexicon).is_ok());
+        assert!(parse_sentence("knows student", &lexicon).is_err());
+        
+        assert_eq!("=DP|CP".parse::<Feature>(), Ok(knows.feats[1].clone()));
+        assert!("=DP|X".parse::<Feature>().is_err());
+        
+        // Either alternative keeps the item productive, so V can embed itself
+        let says = LexItem::new("says", &[Feature::Cat(Category::V), Feature::SelAny(vec![Category::V, Category::DP])]);
+        assert!(is_language_infinite(&[says.clone(), it], Category::V));
+        assert!(!is_language_infinite(&[says], Category::V));
     }
 
     #[test]
-    fn test_recursive_capability() {
-        for n in 0..=5 {
-            assert!(can_generate("an_bn", n));
+    fn test_workspace_summary() {
+        let lexicon = test_lexicon();
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT).with_node_cost(uniform_node_cost);
+        load_tokens(&mut ws, &["the", "student", "left"], &lexicon).unwrap();
+        assert_eq!(ws.summary(), concat!(
+            "step 0, memory 3/4096, 1 mergeable pair(s)\n",
+            "[0] \"the\" [Cat(D), Sel(N)]\n",
+            "[1] \"student\" [Cat(N)]\n",
+            "[2] \"left\" [Cat(V)]\n",
+        ));
+        
+        // Stalled: the DP and the verb cannot combine
+        step(&mut ws).unwrap();
+        assert_eq!(ws.summary(), concat!(
+            "step 1, memory 4/4096, 0 mergeable pair(s)\n",
+            "[0] \"left\" [Cat(V)]\n",
+            "[1] \"the student\" [Cat(D)]\n",
+        ));
+    }
+
+    #[test]
+    fn test_adverbial_modifiers() {
+        let lexicon = modifier_lexicon();
+        
+        let vp = parse_sentence("quickly left", &lexicon).unwrap();
+        assert_eq!(vp.label, Category::V);
+        assert!(vp.is_saturated());
+        assert_eq!(vp.lexical_head().phon.as_deref(), Some("left"));
+        assert_eq!(vp.linearize(), "quickly left");
+        
+        // Once the preposition has its object it projects a PP, which
+        // adjoins to the verb
+        let obj = |phon: &str| SyntacticObject::from_lex(lexicon.iter().find(|item| item.phon == phon).unwrap());
+        let dp = merge(obj("the"), obj("student")).unwrap();
+        let pp = merge(obj("on"), dp).unwrap();
+        assert_eq!(pp.label, Category::PP);
+        assert!(pp.features.contains(&Feature::Cat(Category::PP)));
+        assert_eq!(pp.lexical_head().phon.as_deref(), Some("on"));
+        let vp = merge(pp.clone(), obj("left")).unwrap();
+        assert_eq!(vp.label, Category::V);
+        assert!(vp.is_saturated());
+        assert_eq!(vp.children[0], pp);
+        assert_eq!(vp.linearize(), "on the student left");
+        
+        // The same tree from a workspace that only merges neighbours
+        let mut workspace = Workspace::new(DEFAULT_MEMORY_LIMIT).with_adjacency();
+        load_tokens(&mut workspace, &["the", "student", "on", "left"], &lexicon).unwrap();
+        assert!(derive(&mut workspace, DEFAULT_MAX_STEPS).unwrap().structurally_equivalent(&vp));
+        
+        // Without an object the preposition stays a P; it can still adjoin,
+        // but the verb then inherits its selector
+        let bare = merge(obj("on"), obj("left")).unwrap();
+        assert_eq!(bare.children[0].label, Category::P);
+        assert!(bare.features.contains(&Feature::Sel(Category::D)));
+        assert!(parse_sentence("on left", &lexicon).is_err());
+        assert_eq!("PP".parse::<Category>(), Ok(Category::PP));
+    }
+
+    #[test]
+    fn test_lexical_ambiguity() {
+        let lexicon = vec![
+            LexItem::new("that", &[Feature::Cat(Category::C), Feature::Sel(Category::S)]),
+            LexItem::new("that", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+            LexItem::new("student", &[Feature::Cat(Category::N)]),
+        ];
+        
+        let entries = lookup_all(&lexicon, "that");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].feats[0], Feature::Cat(Category::D));
+        assert!(lookup_all(&lexicon, "who").is_empty());
+        
+        // The greedy parser only ever tries the complementizer
+        assert!(parse_sentence("that student", &lexicon).is_err());
+        let parses = parse_all("that student", &lexicon, DEFAULT_MAX_STEPS);
+        assert_eq!(parses.len(), 1);
+        assert_eq!(parses[0].label, Category::D);
+    }
+
+    #[test]
+    fn test_workspace_reset() {
+        let lexicon = test_lexicon();
+        let mut ws = Workspace::new(512).with_merge_policy(MergePolicy::FewestFeatures);
+        load_tokens(&mut ws, &["the", "student", "the", "tutor"], &lexicon).unwrap();
+        step(&mut ws).unwrap();
+        let capacity = ws.items.capacity();
+        
+        ws.reset();
+        assert!(ws.items.is_empty());
+        assert_eq!(ws.items.capacity(), capacity);
+        assert_eq!((ws.step_count, ws.merge_attempts(), ws.merge_successes()), (0, 0, 0));
+        assert_eq!(ws.memory_limit, 512);
+        assert_eq!(ws.merge_policy, MergePolicy::FewestFeatures);
+        
+        // The reset workspace derives like a fresh one
+        load_tokens(&mut ws, &["the", "student"], &lexicon).unwrap();
+        assert_eq!(derive(&mut ws, DEFAULT_MAX_STEPS), parse_sentence("the student", &lexicon));
+    }
+
+    #[test]
+    fn test_find_mergeable_pairs_matches_full_scan() {
+        let mut lexicon = test_lexicon();
+        lexicon.extend(space_operations_lexicon());
+        lexicon.push(LexItem::new("TEMP_SYNC", &[Feature::Cat(Category::State), Feature::Sel(Category::State), Feature::Symmetric]));
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        for item in &lexicon {
+            ws.add_lex(item);
         }
+        
+        let mut expected = Vec::new();
+        for i in 0..ws.items.len() {
+            for j in (0..ws.items.len()).filter(|&j| j != i) {
+                if can_merge(&ws.items[i], &ws.items[j]) {
+                    expected.push((i, j));
+                }
+            }
+        }
+        assert!(expected.len() > 10);
+        assert_eq!(find_mergeable_pairs(&ws), expected);
     }
 
     #[test]
-    fn test_merge_operation() {
-        let det = SyntacticObject::from_lex(&LexItem::new("the", &[Feature::Cat(Category::D)]));
-        let noun = SyntacticObject::from_lex(&LexItem::new("student", &[Feature::Cat(Category::N)]));
+    fn test_null_complementizer() {
+        let lexicon = vec![
+            LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+            LexItem::new("student", &[Feature::Cat(Category::N)]),
+            LexItem::new("left", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("knew", &[Feature::Cat(Category::V), Feature::Sel(Category::C)]),
+            LexItem::covert(&[Feature::Cat(Category::C), Feature::Sel(Category::V)]),
+        ];
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        ws.merge_policy = MergePolicy::FewestFeatures;
+        load_tokens(&mut ws, &["knew", "the", "student", "left"], &lexicon).unwrap();
+        assert_eq!(ws.items.len(), 5);
         
-        // This should fail - no selector feature
-        assert!(merge(det.clone(), noun.clone()).is_err());
+        let tree = derive(&mut ws, DEFAULT_MAX_STEPS).unwrap();
+        assert_eq!(tree.to_bracketed(), "[V [V knew] [C [C ε] [V [V left] [D [D the] [N student]]]]]");
+        assert_eq!(tree.validate(), Ok(()));
         
-        // Create proper selector
-        let det_sel = SyntacticObject {
-            features: vec![Feature::Sel(Category::N)],
-            ..det
+        // The silent C takes up no position in the string
+        assert_eq!(tree.linearize(), "knew left the student");
+        assert_eq!(tree.linearize_with(&LinearOrder::new()), "knew left the student");
+        assert_eq!(tree.children[1].linearize(), "left the student");
+        assert_eq!(tree.children[1].children[0].linearize(), "");
+        assert!(tree.to_ptb().contains("(C (-NONE- 0) (V"));
+        
+        // Lexicon files write the covert item's phon as ε
+        let parsed = parse_lexicon("ε :: C =V").unwrap();
+        assert_eq!(parsed, vec![LexItem::covert(&[Feature::Cat(Category::C), Feature::Sel(Category::V)])]);
+        assert!(parsed[0].is_covert());
+    }
+
+    #[test]
+    fn test_lexicon_builder() {
+        let built = LexiconBuilder::new()
+            .words(&["the", "a"]).cat(Category::D).sel(Category::N).done()
+            .words(&["student", "tutor", "teacher"]).cat(Category::N).done()
+            .words(&["who", "that"]).cat(Category::C).sel(Category::S).done()
+            .word("said").cat(Category::V).sel(Category::DP).pos(1).done()
+            .word("thinks").cat(Category::V).sel(Category::DP).done()
+            .words(&["left", "smiled", "arrived"]).cat(Category::V).done()
+            .build();
+        assert_eq!(built, test_lexicon());
+        
+        let weighted = LexiconBuilder::new().word("what").cat(Category::D).neg(1).weight(0.5).done().build();
+        assert_eq!(weighted, vec![LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1)]).with_weight(0.5)]);
+        
+        // Collecting and extending keep item order
+        let mut collected: LexiconBuilder = test_lexicon().into_iter().take(2).collect();
+        collected.extend(test_lexicon().into_iter().skip(2));
+        assert_eq!(collected.build(), test_lexicon());
+    }
+
+    #[test]
+    fn test_derive_minimal() {
+        // Each word also has a reading with movement features, giving a
+        // 5-step derivation (3 merges, 2 moves) besides the 3-step ones
+        let lexicon = vec![
+            LexItem::new("what", &[Feature::Cat(Category::D)]),
+            LexItem::new("what", &[Feature::Cat(Category::D), Feature::Neg(1), Feature::Neg(2)]),
+            LexItem::new("saw", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V)]),
+            LexItem::new("did", &[Feature::Cat(Category::S), Feature::Sel(Category::V), Feature::Pos(1)]),
+            LexItem::new("focus", &[Feature::Cat(Category::C), Feature::Sel(Category::S)]),
+            LexItem::new("focus", &[Feature::Cat(Category::C), Feature::Sel(Category::S), Feature::Pos(2)]),
+        ];
+        let sentence = "saw what did focus";
+        let parses = parse_all(sentence, &lexicon, DEFAULT_MAX_STEPS);
+        let steps: Vec<usize> = parses.iter().map(SyntacticObject::derivation_steps).collect();
+        assert!(steps.contains(&3) && steps.contains(&5));
+        assert!(steps.iter().filter(|&&n| n == 3).count() > 1);
+        
+        // The 3-step parses tie, so the bracketing decides
+        let minimal = derive_minimal(sentence, &lexicon, DEFAULT_MAX_STEPS).unwrap();
+        assert_eq!(minimal.derivation_steps(), 3);
+        assert_eq!(minimal.to_bracketed(), "[C [C [C [C focus] [S did]] [V saw]] [D what]]");
+        
+        assert_eq!(
+            derive_minimal("saw who", &lexicon, DEFAULT_MAX_STEPS),
+            Err(DerivationError::UnknownToken { token: "who".to_string(), index: 1, byte_offset: 4 }),
+        );
+    }
+
+    #[test]
+    fn test_unknown_token_position() {
+        let mut lexicon = test_lexicon();
+        lexicon.push(LexItem::new("café", &[Feature::Cat(Category::N)]));
+        
+        // "é" takes two bytes and the run of spaces three, so the offset is
+        // not the character count or the single-space join
+        let sentence = "the café   unicorn";
+        let expected = DerivationError::UnknownToken { token: "unicorn".to_string(), index: 2, byte_offset: 12 };
+        assert_eq!(&sentence[12..], "unicorn");
+        assert_eq!(parse_sentence(sentence, &lexicon), Err(expected.clone()));
+        assert_eq!(parse_traced(sentence, &lexicon).map(|(tree, _)| tree), Err(expected.clone()));
+        assert_eq!(parse_chunked(sentence, &lexicon, 2), Err(expected));
+        
+        // A token stream has no original spacing, so offsets assume one space
+        let mut parser = IncrementalParser::new(&lexicon);
+        parser.feed("café").unwrap();
+        assert_eq!(
+            parser.feed("unicorn"),
+            Err(DerivationError::UnknownToken { token: "unicorn".to_string(), index: 1, byte_offset: 6 })
+        );
+    }
+
+    #[test]
+    fn test_require_adjacency() {
+        let lexicon = test_lexicon();
+        let workspace = |sentence: &str| {
+            let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+            let tokens: Vec<&str> = sentence.split_whitespace().collect();
+            load_tokens(&mut ws, &tokens, &lexicon).unwrap();
+            ws
         };
         
-        // This should succeed
-        let merged = merge(det_sel, noun).unwrap();
-        assert_eq!(merged.label, Category::D);
+        // "the" and "student" can merge, but "who" stands between them
+        let mut ws = workspace("the who student");
+        assert_eq!(find_mergeable_pairs(&ws), vec![(0, 2)]);
+        ws.require_adjacency = true;
+        assert!(find_mergeable_pairs(&ws).is_empty());
+        assert_eq!(step(&mut ws), Err(DerivationError::NoValidOperations));
+        
+        // Neighbours merge, and the result keeps their position
+        let mut ws = workspace("the student who").with_adjacency();
+        assert_eq!(find_mergeable_pairs(&ws), vec![(0, 1)]);
+        step(&mut ws).unwrap();
+        assert_eq!(ws.items[0].linearize(), "the student");
+        assert_eq!(ws.items[1].linearize(), "who");
     }
 
     #[test]
-    fn test_workspace_operations() {
-        let mut workspace = Workspace::new(1024);
+    fn test_explain_failure() {
+        let lexicon = space_operations_lexicon();
+        
+        // CURRENT_DRAW's selector percolates to the root but is still its own
+        let (result, ws) = parse_sentence_debug("CTX_DRIVE MOTOR_CMD_START CURRENT_DRAW", &lexicon);
+        assert_eq!(result, Err(DerivationError::NoValidOperations));
+        assert_eq!(explain_failure(&ws), "CURRENT_DRAW expected a following State but the sequence ended");
+        
+        let (_, ws) = parse_sentence_debug("CTX_STANDBY VOLTAGE_SPIKE", &lexicon);
+        assert_eq!(
+            explain_failure(&ws),
+            "CTX_STANDBY expected a following Command but found VOLTAGE_SPIKE (State)\n\
+             VOLTAGE_SPIKE (State) was left unattached"
+        );
+        
+        // A wh-word with no complementizer to attract it
+        let no_c: Vec<LexItem> = wh_movement_lexicon().into_iter().filter(|item| !item.is_covert()).collect();
+        let (_, ws) = parse_sentence_debug("who left", &no_c);
+        assert!(explain_failure(&ws).contains("who carries Neg(1)"));
+        
+        let (result, ws) = parse_sentence_debug("CTX_DRIVE MOTOR_CMD_START VOLTAGE_SPIKE", &lexicon);
+        assert!(result.is_ok());
+        assert_eq!(explain_failure(&ws), "");
+    }
+
+    #[cfg(feature = "intern")]
+    #[test]
+    fn test_interned_phonology() {
         let lexicon = test_lexicon();
+        let the = lexicon.iter().find(|item| item.phon == "the").unwrap();
+        let student = lexicon.iter().find(|item| item.phon == "student").unwrap();
         
-        workspace.add_lex(&lexicon[0]); // "the"
-        workspace.add_lex(&lexicon[2]); // "student"
+        let mut ws = Workspace::new(DEFAULT_MEMORY_LIMIT);
+        ws.add_lex(the);
+        ws.add_lex(student);
+        let first = derive(&mut ws, DEFAULT_MAX_STEPS).unwrap();
         
-        assert_eq!(workspace.items.len(), 2);
-        assert!(!workspace.is_successful());
+        ws.reset();
+        ws.add_lex(the);
+        ws.add_lex(student);
+        let second = derive(&mut ws, DEFAULT_MAX_STEPS).unwrap();
+        
+        // Both derivations point at the same two strings
+        assert_eq!(ws.interner().len(), 2);
+        let phon = |tree: &SyntacticObject| tree.children[0].phon.clone().unwrap();
+        assert!(std::sync::Arc::ptr_eq(&phon(&first), &phon(&second)));
+        assert_eq!(second.linearize(), "the student");
+    }
+
+    #[test]
+    fn test_multi_word_entries() {
+        let mut lexicon = test_lexicon();
+        lexicon.extend(parse_lexicon("kick :: V =D\nkick the bucket :: V\nbucket :: N").unwrap());
+        
+        // The idiom wins over "kick" followed by a DP
+        let tree = parse_sentence("kick the bucket", &lexicon).unwrap();
+        assert!(tree.children.is_empty());
+        assert_eq!(tree.phon.as_deref(), Some("kick the bucket"));
+        assert_eq!(tree.label, Category::V);
+        assert!(recognize("kick  the bucket", &lexicon));
+        
+        // Without the whole idiom, the words parse compositionally
+        let tree = parse_sentence("kick the student", &lexicon).unwrap();
+        assert_eq!(tree.lexical_head().phon.as_deref(), Some("kick"));
+        assert_eq!(tree.linearize(), "kick the student");
+        assert_eq!(
+            parse_sentence("kick the", &lexicon),
+            Err(DerivationError::NoValidOperations)
+        );
+        
+        // Every entry point groups the words the same way
+        let idiom = parse_sentence("kick the bucket", &lexicon).unwrap();
+        assert_eq!(parse_chunked("kick the bucket", &lexicon, 1).unwrap(), idiom);
+        assert_eq!(parse_chunked("kick the student", &lexicon, 1).unwrap(), parse_sentence("kick the student", &lexicon).unwrap());
+        let log: Vec<String> = ["kick the bucket", "bucket"].iter().map(|s| s.to_string()).collect();
+        let anomalies = find_mission_log_anomalies(&log, &lexicon);
+        assert!(anomalies.iter().all(|anomaly| anomaly.reason == AnomalyReason::UngrammaticalSequence));
+        
+        // The incremental parser holds back words that may begin the idiom
+        let mut parser = IncrementalParser::new(&lexicon);
+        parser.feed("kick").unwrap();
+        parser.feed("the").unwrap();
+        assert!(parser.current_state().items.is_empty());
+        parser.feed("bucket").unwrap();
+        assert_eq!(parser.current_state().items, vec![idiom]);
+        
+        let mut parser = IncrementalParser::new(&lexicon);
+        for word in ["kick", "the", "student"] {
+            parser.feed(word).unwrap();
+        }
+        assert!(parser.current_state().is_successful());
+        assert_eq!(parser.current_state().items[0].linearize(), "kick the student");
+        
+        let mut parser = IncrementalParser::new(&lexicon);
+        parser.feed("kick").unwrap();
+        parser.feed("the").unwrap();
+        parser.finish().unwrap();
+        assert!(!parser.current_state().items.is_empty());
+        assert!(!parser.current_state().is_successful());
+    }
+
+    #[test]
+    fn test_agreement() {
+        let lexicon = LexiconBuilder::new()
+            .word("runs").cat(Category::V).sel(Category::D).agr("sg").done()
+            .word("run").cat(Category::V).sel(Category::D).agr("pl").done()
+            .word("ran").cat(Category::V).sel(Category::D).done()
+            .word("this").cat(Category::D).agr("sg").done()
+            .word("these").cat(Category::D).agr("pl").done()
+            .build();
+        let leaf = |phon: &str| SyntacticObject::from_lex(lexicon.iter().find(|item| item.phon == phon).unwrap());
+        
+        let tree = parse_sentence("runs this", &lexicon).unwrap();
+        assert!(tree.is_saturated());
+        assert_eq!(tree.features, vec![Feature::Cat(Category::V), Feature::Agr("sg".to_string())]);
+        assert_eq!(tree.feature_summary().agreements, 1);
+        
+        // Selection alone would allow it, but the values differ
+        assert!(!can_merge(&leaf("runs"), &leaf("these")));
+        assert_eq!(merge(leaf("runs"), leaf("these")), Err(DerivationError::FeatureMismatch {
+            expected: Some(Feature::Agr("sg".to_string())),
+            found: Some(Feature::Agr("pl".to_string())),
+        }));
+        assert_eq!(parse_sentence("runs these", &lexicon), Err(DerivationError::NoValidOperations));
+        
+        // Agreement only constrains pairs where both sides carry a value
+        assert!(parse_sentence("run these", &lexicon).is_ok());
+        let tree = parse_sentence("ran these", &lexicon).unwrap();
+        assert_eq!(tree.feature_summary().agreements, 0);
     }
 
+    #[cfg(feature = "pyo3")]
     #[test]
     fn test_mission_log_validation() {
         // Grammatical sequence
@@ -725,3 +7238,47 @@ mod tests {
         assert!(!validate_mission_log(anomaly_log).unwrap().is_empty());
     }
 }
+
+#[cfg(all(test, feature = "no_std"))]
+mod no_std_tests {
+    use super::*;
+    
+    #[test]
+    fn test_generate_an_bn_without_std() {
+        assert_eq!(generate_an_bn(0), "");
+        assert_eq!(generate_an_bn(1), "a b");
+        assert_eq!(generate_an_bn(3), "a a a b b b");
+        assert!(is_an_bn_pattern(&generate_an_bn(4)));
+    }
+}
+
+#[cfg(all(test, feature = "proptest"))]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+    
+    /// Random token sequences over a lexicon's words; most do not parse, and
+    /// those that do are the random derivations under test
+    fn sentences(lexicon: &[LexItem]) -> impl Strategy<Value = String> {
+        let words: Vec<String> = lexicon.iter().map(|item| item.phon.clone()).filter(|phon| !phon.is_empty()).collect();
+        prop::collection::vec(prop::sample::select(words), 1..6).prop_map(|words| words.join(" "))
+    }
+    
+    proptest! {
+        #[test]
+        fn parsed_trees_round_trip(sentence in sentences(&test_lexicon())) {
+            let lexicon = test_lexicon();
+            if let Ok(tree) = parse_sentence(&sentence, &lexicon) {
+                prop_assert!(round_trip_check(&tree, &lexicon), "{} -> {}", sentence, tree.linearize());
+            }
+        }
+        
+        #[test]
+        fn moved_trees_round_trip(sentence in sentences(&wh_movement_lexicon())) {
+            let lexicon = wh_movement_lexicon();
+            if let Ok(tree) = parse_sentence(&sentence, &lexicon) {
+                prop_assert!(round_trip_check(&tree, &lexicon), "{} -> {}", sentence, tree.linearize());
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Enforce the <50kB code size claim
+//!
+//! Builds `examples/size_check.rs` and `examples/size_baseline.rs` in
+//! release mode and compares their `.text` sections. Everything but the
+//! parser, i.e. std's runtime, is common to both, so the difference is the
+//! code a program pays for calling `parse_sentence`.
+//!
+//! The release builds go to their own target directory under
+//! `CARGO_TARGET_TMPDIR`, so only the first run pays for a full build.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Most `.text` bytes the parser may add to a binary
+const MAX_PARSER_TEXT_BYTES: u64 = 50 * 1024;
+
+/// Build an example in release mode, returning the binary's path
+fn build_example(name: &str) -> PathBuf {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("binary_size");
+    let status = Command::new(option_env!("CARGO").unwrap_or("cargo"))
+        .args(["build", "--release", "--no-default-features", "--features", "std", "--example", name])
+        .arg("--manifest-path")
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success(), "building example {} failed", name);
+    target_dir.join("release").join("examples").join(name)
+}
+
+/// Size of the named section of a little-endian ELF64 file
+fn elf_section_size(elf: &[u8], section: &str) -> Option<u64> {
+    let u16_at = |at: usize| u16::from_le_bytes(elf[at..at + 2].try_into().unwrap()) as usize;
+    let u32_at = |at: usize| u32::from_le_bytes(elf[at..at + 4].try_into().unwrap()) as usize;
+    let u64_at = |at: usize| u64::from_le_bytes(elf[at..at + 8].try_into().unwrap());
+    
+    if elf.get(..6)? != b"\x7fELF\x02\x01" {
+        return None;
+    }
+    let (shoff, shentsize, shnum, shstrndx) = (u64_at(0x28) as usize, u16_at(0x3a), u16_at(0x3c), u16_at(0x3e));
+    let header = |k: usize| shoff + k * shentsize;
+    let names = u64_at(header(shstrndx) + 0x18) as usize;
+    
+    (0..shnum).find_map(|k| {
+        let name = &elf[names + u32_at(header(k))..];
+        let name = &name[..name.iter().position(|&b| b == 0)?];
+        (name == section.as_bytes()).then(|| u64_at(header(k) + 0x20))
+    })
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_parser_text_size() {
+    let text_size = |name: &str| {
+        let elf = std::fs::read(build_example(name)).unwrap();
+        elf_section_size(&elf, ".text").expect("no .text section")
+    };
+    let with_parser = text_size("size_check");
+    let baseline = text_size("size_baseline");
+    
+    let parser = with_parser.saturating_sub(baseline);
+    println!(".text: {} bytes with the parser, {} without; parser {} bytes", with_parser, baseline, parser);
+    assert!(
+        parser <= MAX_PARSER_TEXT_BYTES,
+        "parse_sentence adds {} bytes of code, over the {} byte budget",
+        parser,
+        MAX_PARSER_TEXT_BYTES,
+    );
+}
@@ -0,0 +1,69 @@
+//! End-to-end checks of the `atomic-lang-model` binary
+//!
+//! Run with `cargo test --features cli --test cli`.
+
+#![cfg(feature = "cli")]
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_atomic-lang-model"))
+        .args(args)
+        .output()
+        .expect("failed to run the CLI")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn parse_prints_the_bracketed_tree() {
+    let output = cli(&["parse", "the student"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "[D [D the] [N student]]\n");
+
+    let output = cli(&["parse", "the unicorn"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("Unknown token: unicorn"));
+}
+
+#[test]
+fn validate_reports_each_line() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("mission.log");
+    std::fs::write(&path, "CTX_DRIVE MOTOR_CMD_START VOLTAGE_SPIKE\n\nCTX_STANDBY VOLTAGE_SPIKE\n").unwrap();
+
+    let output = cli(&["validate", path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let report = stdout(&output);
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], "line 1: ok");
+    assert!(lines[1].starts_with("line 3: Anomaly Detected: Ungrammatical sequence 'CTX_STANDBY' followed by 'VOLTAGE_SPIKE'"));
+}
+
+#[test]
+fn generate_prints_the_pattern() {
+    let output = cli(&["generate", "an_bn", "3"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "a a a b b b\n");
+}
+
+#[test]
+fn usage_errors_exit_with_status_2() {
+    for args in [&[][..], &["generate", "an_bn"], &["generate", "abc", "2"], &["frobnicate", "x"]] {
+        let output = cli(args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("usage: atomic-lang-model"));
+    }
+}
+
+#[test]
+fn demo_walks_through_the_model() {
+    let output = cli(&["demo"]);
+    assert!(output.status.success());
+    let report = stdout(&output);
+    assert!(report.contains("n=3: a a a b b b"));
+    assert!(report.contains("Demo complete"));
+}
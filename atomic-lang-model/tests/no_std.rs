@@ -0,0 +1,31 @@
+//! Build the library without std
+//!
+//! The `no_std` feature drops std for `alloc`, so the library is checked as
+//! an rlib: the cdylib target needs a panic handler and a global allocator,
+//! which an embedded application supplies itself. The library's own tests
+//! then run with the feature set, which compiles the `no_std_tests` module.
+//!
+//! The builds go to their own target directory under `CARGO_TARGET_TMPDIR`,
+//! so only the first run pays for a full build.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Run cargo on this package with the `no_std` feature set
+fn cargo_no_std(args: &[&str]) -> bool {
+    Command::new(option_env!("CARGO").unwrap_or("cargo"))
+        .args(args)
+        .args(["--no-default-features", "--features", "no_std"])
+        .arg("--manifest-path")
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", Path::new(env!("CARGO_TARGET_TMPDIR")).join("no_std"))
+        .status()
+        .expect("failed to run cargo")
+        .success()
+}
+
+#[test]
+fn test_library_builds_without_std() {
+    assert!(cargo_no_std(&["rustc", "--lib", "--crate-type", "rlib"]), "building the rlib without std failed");
+    assert!(cargo_no_std(&["test", "--lib"]), "the library's tests failed without std");
+}
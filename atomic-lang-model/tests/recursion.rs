@@ -4,7 +4,23 @@
 //! implements truly recursive computation, demonstrating unbounded generation
 //! capability with finite computational resources.
 
-use atomic_lang_model::*;
+use atomic_lang_model_python::*;
+
+/// Determiners, nouns and intransitive verbs that select their subject
+///
+/// `test_lexicon()` gives its intransitive verbs no selector, so nothing in
+/// it combines a DP with a verb and "the student smiled" has no parse there.
+fn subject_verb_lexicon() -> Vec<LexItem> {
+    vec![
+        LexItem::new("the", &[Feature::Cat(Category::D), Feature::Sel(Category::N)]),
+        LexItem::new("student", &[Feature::Cat(Category::N)]),
+        LexItem::new("tutor", &[Feature::Cat(Category::N)]),
+        LexItem::new("teacher", &[Feature::Cat(Category::N)]),
+        LexItem::new("smiled", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+        LexItem::new("left", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+        LexItem::new("arrived", &[Feature::Cat(Category::V), Feature::Sel(Category::D)]),
+    ]
+}
 
 #[test]
 fn test_grammar_adequacy_an_bn() {
@@ -12,13 +28,12 @@ fn test_grammar_adequacy_an_bn() {
     println!("Testing aⁿbⁿ generation for mathematical proof...");
     
     for n in 0..=9 {
-        let pattern = generate_pattern("an_bn", n).expect(&format!("Failed to generate a^{}b^{}", n, n));
+        let pattern = generate_pattern("an_bn", n).unwrap_or_else(|_| panic!("Failed to generate a^{}b^{}", n, n));
         
         // Verify the pattern is correct
         assert!(is_an_bn_pattern(&pattern), "Generated pattern a^{}b^{} is invalid: '{}'", n, n, pattern);
         
         // Verify length grows linearly
-        let expected_length = if n == 0 { 0 } else { 2 * n + 1 }; // Account for space
         if pattern.is_empty() {
             assert_eq!(pattern.len(), 0);
         } else {
@@ -36,7 +51,9 @@ fn test_grammar_adequacy_an_bn() {
 
 #[test]
 fn test_nested_relative_parsing() {
-    let lexicon = test_lexicon();
+    let lexicon = subject_verb_lexicon();
+    // The subject is the verb's selectee, pronounced before it
+    let subject_first = LinearOrder::new().with_direction(Category::V, HeadDirection::Final);
     
     // Test increasingly complex nested structures
     let test_cases = vec![
@@ -57,7 +74,7 @@ fn test_nested_relative_parsing() {
                     assert!(tree.is_complete() || !tree.features.is_empty(), "Parse tree incomplete");
                     
                     // Verify linearization matches input
-                    let linearized = tree.linearize();
+                    let linearized = tree.linearize_with(&subject_first);
                     // Note: linearization might differ in spacing, so compare tokens
                     let input_tokens: Vec<&str> = sentence.split_whitespace().collect();
                     let output_tokens: Vec<&str> = linearized.split_whitespace().collect();
@@ -127,8 +144,6 @@ fn test_recursive_depth_scaling() {
         workspace.add_lex(&lexicon[2]); // "student" 
         workspace.add_lex(&lexicon[9]); // "left"
         
-        let initial_usage = workspace.memory_usage();
-        
         // Simulate derivation steps
         let mut step_count = 0;
         while step_count < 10 {
@@ -166,7 +181,6 @@ fn test_feature_system_correctness() {
     
     // Test feature matching and checking
     let cat_n = Feature::Cat(Category::N);
-    let sel_n = Feature::Sel(Category::N);
     let pos_wh = Feature::Pos(1);
     let neg_wh = Feature::Neg(1);
     
@@ -191,27 +205,34 @@ fn test_merge_operation_correctness() {
         label: Category::D,
         features: vec![Feature::Sel(Category::N)], // Selector for N
         children: Vec::new(),
-        phon: Some("the".to_string()),
+        phon: Some("the".into()),
+        provenance: None,
+        trace: None,
     };
     
     let noun = SyntacticObject {
         label: Category::N,
         features: vec![Feature::Cat(Category::N)], // Category N
         children: Vec::new(),
-        phon: Some("student".to_string()),
+        phon: Some("student".into()),
+        provenance: None,
+        trace: None,
     };
     
     let verb = SyntacticObject {
         label: Category::V,
         features: vec![Feature::Cat(Category::V)], // Category V
         children: Vec::new(),
-        phon: Some("left".to_string()),
+        phon: Some("left".into()),
+        provenance: None,
+        trace: None,
     };
     
-    // Test successful merge: Det[=N] + N → NP
+    // Test successful merge: Det[=N] + N → DP. The selecting head projects,
+    // so the result is labelled D, as merge has always labelled it
     match merge(det.clone(), noun.clone()) {
         Ok(result) => {
-            assert_eq!(result.label, Category::N); // Result takes category from selected item
+            assert_eq!(result.label, Category::D);
             assert_eq!(result.children.len(), 2);
             println!("✅ Successful merge: Det[=N] + N → {:?}", result.label);
         }
@@ -221,7 +242,7 @@ fn test_merge_operation_correctness() {
     // Test failed merge: Det[=N] + V (category mismatch)
     match merge(det.clone(), verb.clone()) {
         Ok(_) => panic!("Expected merge failure for category mismatch"),
-        Err(DerivationError::FeatureMismatch) => {
+        Err(DerivationError::FeatureMismatch { .. }) => {
             println!("✅ Correctly rejected incompatible merge: Det[=N] + V");
         }
         Err(e) => panic!("Unexpected error type: {}", e),
@@ -232,12 +253,14 @@ fn test_merge_operation_correctness() {
         label: Category::D,
         features: vec![Feature::Cat(Category::D)], // No selector
         children: Vec::new(),
-        phon: Some("the".to_string()),
+        phon: Some("the".into()),
+        provenance: None,
+        trace: None,
     };
     
     match merge(plain_det, noun) {
         Ok(_) => panic!("Expected merge failure without selector"),
-        Err(DerivationError::FeatureMismatch) => {
+        Err(DerivationError::FeatureMismatch { .. }) => {
             println!("✅ Correctly rejected merge without selector");
         }
         Err(e) => panic!("Unexpected error type: {}", e),
@@ -320,7 +343,7 @@ fn test_mathematical_properties() {
 #[test]
 fn test_complete_recursive_proof() {
     println!("\n🧮 COMPLETE MATHEMATICAL PROOF OF RECURSION");
-    println!("=" .repeat(50));
+    println!("{}", "=".repeat(50));
     
     // 1. Prove non-regularity through aⁿbⁿ generation
     println!("\n1. Non-regularity proof via aⁿbⁿ:");